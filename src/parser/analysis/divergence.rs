@@ -0,0 +1,150 @@
+//! Analyse de divergence des blocs
+//! Un bloc diverge lorsque l'exécution ne peut atteindre sa fin, par exemple parce que
+//! tous ses chemins se terminent par un `return`.
+use ast::*;
+use token::Keyword;
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// Le bloc `else` d'un `let ... else` peut se terminer sans diverger
+    #[fail(display = "Le bloc `else` de la déclaration de '{}' doit diverger (`return`)", 0)]
+    LetElseFallthrough(String),
+}
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::LetElseFallthrough(..) => "E0103",
+        }
+    }
+}
+
+/// Si l'exécution du bloc ne peut jamais atteindre sa fin
+pub fn diverges(block: &Block) -> bool {
+    // si toutes les branches de la clause en cours divergent
+    let mut branches_diverge = false;
+
+    for stmt in block.statements() {
+        match *stmt {
+            Statement::Return(_) => return true,
+            Statement::Conditional(ref cond) => match cond.token {
+                Keyword::If | Keyword::Unless => branches_diverge = diverges(&cond.body),
+                Keyword::Elseif => branches_diverge &= diverges(&cond.body),
+                // le `else` complète la clause: un de ses chemins est toujours emprunté
+                Keyword::Else => if branches_diverge && diverges(&cond.body) {
+                    return true;
+                },
+                _ => {},
+            },
+            Statement::Loop(ref looping) => if is_infinite(looping) {
+                return true;
+            },
+            _ => branches_diverge = false,
+        }
+    }
+    false
+}
+
+/// Une boucle `while true` ne se termine jamais puisque le langage n'a pas encore de `break`
+fn is_infinite(looping: &LoopStatement) -> bool {
+    match looping.condition {
+        Some(ref condition) => **condition == Expression::Literal(Literal::Boolean(true)),
+        None => false,
+    }
+}
+
+/// Vérifie que le bloc `else` de chaque `let ... else` de la fonction diverge
+pub fn check_let_else(function: &FunctionDeclaration) -> Vec<Error> {
+    let mut errors = Vec::new();
+    check_block(&function.body, &mut errors);
+    errors
+}
+
+fn check_block(block: &Block, errors: &mut Vec<Error>) {
+    for stmt in block.statements() {
+        match *stmt {
+            Statement::Conditional(ref cond) => check_block(&cond.body, errors),
+            Statement::Loop(ref looping) => check_block(&looping.body, errors),
+            Statement::LetElse(ref decl, ref otherwise) => {
+                if !diverges(otherwise) {
+                    errors.push(Error::LetElseFallthrough(decl.ident.name.clone()));
+                }
+                check_block(otherwise, errors);
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditional(token: Keyword, body: Vec<Statement>) -> Statement {
+        ConditionalStatement {
+            token,
+            condition: match token {
+                Keyword::Else => None,
+                _ => Some(box "x".into()),
+            },
+            body: box Block::new(body),
+        }.into()
+    }
+
+    fn let_else(name: &str, otherwise: Vec<Statement>) -> Statement {
+        Statement::LetElse(VariableDeclaration {
+            token: Keyword::Let,
+            ident: Variable { name: name.to_string(), category: Type { name: "int".to_string() } },
+            value: box "valeur".into(),
+        }, box Block::new(otherwise))
+    }
+
+    #[test]
+    fn divergence() {
+        let ret = || Statement::Return(None);
+
+        assert!(diverges(&Block::new(vec![Statement::Expression(box "a".into()), ret()])));
+        assert!(!diverges(&Block::new(vec![])));
+        assert!(diverges(&Block::new(vec![
+            conditional(Keyword::If, vec![ret()]),
+            conditional(Keyword::Elseif, vec![ret()]),
+            conditional(Keyword::Else, vec![ret()]),
+        ])));
+        // sans `else`, la clause peut être sautée
+        assert!(!diverges(&Block::new(vec![
+            conditional(Keyword::If, vec![ret()]),
+            conditional(Keyword::Elseif, vec![ret()]),
+        ])));
+        assert!(!diverges(&Block::new(vec![
+            conditional(Keyword::If, vec![ret()]),
+            conditional(Keyword::Else, vec![]),
+        ])));
+        assert!(diverges(&Block::new(vec![LoopStatement {
+            token: Keyword::While,
+            condition: Some(box Literal::Boolean(true).into()),
+            body: box Block::new(vec![]),
+        }.into()])));
+    }
+
+    #[test]
+    fn let_else_must_diverge() {
+        let fun = FunctionDeclaration {
+            identifier: "f".to_string(),
+            parameters: vec![],
+            body: box Block::new(vec![
+                let_else("a", vec![Statement::Return(None)]),
+                let_else("b", vec![Statement::Expression(box "a".into())]),
+                conditional(Keyword::If, vec![
+                    let_else("c", vec![conditional(Keyword::If, vec![Statement::Return(None)])]),
+                ]),
+            ]),
+            return_type: Type { name: "void".to_string() },
+        };
+
+        assert_eq!(vec![
+            Error::LetElseFallthrough("b".to_string()),
+            Error::LetElseFallthrough("c".to_string()),
+        ], check_let_else(&fun));
+    }
+}
@@ -0,0 +1,138 @@
+//! Validation du point d'entrée d'un programme
+//! Un exécutable doit définir exactement une fonction `main` dont la signature est
+//! `fun main() -> int`, `fun main() -> void` ou `fun main(args: [string]) -> int`.
+//! Une librairie n'a pas de point d'entrée et n'est donc pas vérifiée.
+use analysis::interface::DeclSignature;
+use ast::FunctionDeclaration;
+
+/// Le nom de la fonction servant de point d'entrée
+pub const ENTRY_POINT: &str = "main";
+
+/// Une fonction `main` minimale, suggérée lorsque celle-ci est absente
+pub const MAIN_STUB: &str = "fun main() -> int {\n    return 0;\n}";
+
+/// Le type de programme compilé
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    /// Un exécutable, qui doit avoir un point d'entrée
+    Executable,
+    /// Une librairie, qui n'a pas de point d'entrée
+    Library,
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target::Executable
+    }
+}
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// Aucune fonction `main` n'est définie
+    #[fail(display = "Fonction `main` manquante, ajoutez par exemple:\n{}", 0)]
+    MissingMain(&'static str),
+    /// Plusieurs fonctions `main` sont définies
+    #[fail(display = "Fonction `main` définie {} fois", 0)]
+    MultipleMain(usize),
+    /// La signature de `main` n'est pas une des signatures acceptées
+    #[fail(display = "Signature invalide pour `main`: '{}'", 0)]
+    InvalidSignature(String),
+}
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::MissingMain(..) => "E0100",
+            Error::MultipleMain(..) => "E0101",
+            Error::InvalidSignature(..) => "E0102",
+        }
+    }
+}
+
+/// Vérifie le point d'entrée du programme formé des fonctions passées en paramètre
+/// Toutes les erreurs sont renvoyées, par exemple une signature invalide pour chacune
+/// des fonctions `main` définies en double
+pub fn check_entry_point<'a, I>(functions: I, target: Target) -> Result<(), Vec<Error>>
+    where
+        I: IntoIterator<Item = &'a FunctionDeclaration>,
+{
+    if target == Target::Library {
+        return Ok(());
+    }
+
+    let mains: Vec<_> = functions.into_iter()
+        .filter(|fun| fun.identifier == ENTRY_POINT)
+        .collect();
+
+    let mut errors = Vec::new();
+    match mains.len() {
+        0 => errors.push(Error::MissingMain(MAIN_STUB)),
+        1 => {},
+        n => errors.push(Error::MultipleMain(n)),
+    }
+    errors.extend(mains.into_iter()
+        .filter(|fun| !is_valid_signature(fun))
+        .map(|fun| Error::InvalidSignature(DeclSignature::from(fun).to_string())));
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn is_valid_signature(fun: &FunctionDeclaration) -> bool {
+    let params: Vec<_> = fun.parameters.iter().map(|param| param.category.name.as_str()).collect();
+    match (&params[..], fun.return_type.name.as_str()) {
+        (&[], "int") | (&[], "void") | (&["[string]"], "int") => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::*;
+
+    fn function(name: &str, parameters: &[&str], return_type: &str) -> FunctionDeclaration {
+        FunctionDeclaration {
+            identifier: name.to_string(),
+            parameters: parameters.iter().enumerate()
+                .map(|(i, ty)| Variable {
+                    name: format!("arg{}", i),
+                    category: Type { name: ty.to_string() },
+                })
+                .collect(),
+            body: box Block::new(vec![]),
+            return_type: Type { name: return_type.to_string() },
+        }
+    }
+
+    #[test]
+    fn valid_entry_points() {
+        for fun in &[
+            function("main", &[], "int"),
+            function("main", &[], "void"),
+            function("main", &["[string]"], "int"),
+        ] {
+            assert_eq!(Ok(()), check_entry_point(vec![fun], Target::Executable));
+        }
+    }
+
+    #[test]
+    fn invalid_entry_points() {
+        let helper = function("aide", &[], "int");
+        assert_eq!(
+            Err(vec![Error::MissingMain(MAIN_STUB)]),
+            check_entry_point(vec![&helper], Target::Executable)
+        );
+        assert_eq!(Ok(()), check_entry_point(vec![&helper], Target::Library));
+
+        let first = function("main", &[], "int");
+        let second = function("main", &["int"], "string");
+        assert_eq!(
+            Err(vec![
+                Error::MultipleMain(2),
+                Error::InvalidSignature("fun main(arg0: int) -> string".to_string()),
+            ]),
+            check_entry_point(vec![&first, &helper, &second], Target::Executable)
+        );
+    }
+}
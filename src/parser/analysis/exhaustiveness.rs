@@ -0,0 +1,438 @@
+//! Vérification d'exhaustivité et d'accessibilité des `switch`
+//! Repose sur l'algorithme d'utilité de Maranget ("Warnings for pattern matching"):
+//! un motif est utile s'il existe une valeur qu'il accepte et qu'aucun motif précédent
+//! n'accepte. Une branche inutile est inaccessible, et le `switch` est exhaustif
+//! lorsqu'un `_` ajouté à la fin serait inutile.
+//!
+//! Les motifs doivent être bien typés: un constructeur inconnu du type énuméré
+//! n'accepte aucune valeur.
+use token::Span;
+
+use std::fmt;
+
+/// Au-delà de ce nombre, les motifs manquants ne sont pas tous affichés
+const MAX_MISSING: usize = 3;
+
+/// Une valeur constante pouvant apparaître dans un motif
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant {
+    Bool(bool),
+    Int(i64),
+    Char(char),
+    Str(String),
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Constant::Bool(value) => write!(f, "{}", value),
+            Constant::Int(value) => write!(f, "{}", value),
+            Constant::Char(value) => write!(f, "{:?}", value),
+            Constant::Str(ref value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+/// Un motif d'une branche de `switch`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// `_`, accepte toute valeur
+    Wildcard,
+    Literal(Constant),
+    Tuple(Vec<Pattern>),
+    /// Un constructeur d'un type énuméré et les motifs de ses champs
+    Constructor(String, Vec<Pattern>),
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Pattern::Wildcard => f.write_str("_"),
+            Pattern::Literal(ref constant) => write!(f, "{}", constant),
+            Pattern::Tuple(ref fields) => write!(f, "({})", join(fields)),
+            Pattern::Constructor(ref name, ref fields) if fields.is_empty() => f.write_str(name),
+            Pattern::Constructor(ref name, ref fields) => write!(f, "{}({})", name, join(fields)),
+        }
+    }
+}
+
+/// Le type de la valeur examinée par le `switch`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ty {
+    Bool,
+    Int,
+    Char,
+    Str,
+    Tuple(Vec<Ty>),
+    Enum(Vec<Variant>),
+}
+
+/// Un constructeur d'un type énuméré
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<Ty>,
+}
+
+/// Une branche d'un `switch`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arm {
+    pub pattern: Pattern,
+    pub span: Span,
+}
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// Des valeurs ne sont acceptées par aucune branche
+    #[fail(display = "Le `switch` n'est pas exhaustif, motifs manquants: {}", 0)]
+    NonExhaustive(String, Span),
+    /// Les branches précédentes acceptent déjà toutes les valeurs de celle-ci
+    #[fail(display = "Branche inaccessible")]
+    UnreachableArm(Span),
+}
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::NonExhaustive(..) => "E0108",
+            Error::UnreachableArm(..) => "E0109",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match *self {
+            Error::NonExhaustive(_, span) | Error::UnreachableArm(span) => span,
+        }
+    }
+}
+
+/// Vérifie les branches d'un `switch` sur une valeur de type `ty`
+/// `span` est la gamme du `switch`, où est signalée l'absence d'exhaustivité.
+pub fn check_switch(ty: &Ty, arms: &[Arm], span: Span) -> Vec<Error> {
+    let mut errors = Vec::new();
+    let mut rows: Vec<Vec<Pattern>> = Vec::with_capacity(arms.len());
+
+    for arm in arms {
+        let row = vec![arm.pattern.clone()];
+        if !is_useful(&rows, &row, &[ty.clone()]) {
+            errors.push(Error::UnreachableArm(arm.span));
+        }
+        rows.push(row);
+    }
+
+    let missing = witnesses(&rows, &[ty.clone()]);
+    if !missing.is_empty() {
+        let mut listed: Vec<String> = missing.iter()
+            .take(MAX_MISSING)
+            .map(|witness| witness[0].to_string())
+            .collect();
+        if missing.len() > MAX_MISSING {
+            listed.push(format!("et {} de plus", missing.len() - MAX_MISSING));
+        }
+        errors.push(Error::NonExhaustive(listed.join(", "), span));
+    }
+
+    errors
+}
+
+/// Le constructeur en tête d'un motif
+#[derive(Clone, Debug, PartialEq)]
+enum Ctor {
+    Literal(Constant),
+    Tuple,
+    Variant(String),
+}
+
+impl Ctor {
+    fn of(pattern: &Pattern) -> Option<Ctor> {
+        match *pattern {
+            Pattern::Wildcard => None,
+            Pattern::Literal(ref constant) => Some(Ctor::Literal(constant.clone())),
+            Pattern::Tuple(_) => Some(Ctor::Tuple),
+            Pattern::Constructor(ref name, _) => Some(Ctor::Variant(name.clone())),
+        }
+    }
+
+    /// Tous les constructeurs du type, `None` s'ils sont en nombre infini
+    fn all(ty: &Ty) -> Option<Vec<Ctor>> {
+        match *ty {
+            Ty::Bool => Some(vec![
+                Ctor::Literal(Constant::Bool(false)),
+                Ctor::Literal(Constant::Bool(true)),
+            ]),
+            Ty::Tuple(_) => Some(vec![Ctor::Tuple]),
+            Ty::Enum(ref variants) => {
+                Some(variants.iter().map(|variant| Ctor::Variant(variant.name.clone())).collect())
+            },
+            Ty::Int | Ty::Char | Ty::Str => None,
+        }
+    }
+
+    /// Les types des champs du constructeur
+    fn fields(&self, ty: &Ty) -> Vec<Ty> {
+        match (self, ty) {
+            (&Ctor::Tuple, &Ty::Tuple(ref fields)) => fields.clone(),
+            (&Ctor::Variant(ref name), &Ty::Enum(ref variants)) => variants.iter()
+                .find(|variant| variant.name == *name)
+                .map(|variant| variant.fields.clone())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Reconstruit un motif à partir des motifs de ses champs
+    fn apply(&self, fields: Vec<Pattern>) -> Pattern {
+        match *self {
+            Ctor::Literal(ref constant) => Pattern::Literal(constant.clone()),
+            Ctor::Tuple => Pattern::Tuple(fields),
+            Ctor::Variant(ref name) => Pattern::Constructor(name.clone(), fields),
+        }
+    }
+}
+
+/// Les lignes dont le premier motif accepte `ctor`, ce motif remplacé par ses champs
+fn specialize(rows: &[Vec<Pattern>], ctor: &Ctor, arity: usize) -> Vec<Vec<Pattern>> {
+    rows.iter().filter_map(|row| specialize_row(row, ctor, arity)).collect()
+}
+
+fn specialize_row(row: &[Pattern], ctor: &Ctor, arity: usize) -> Option<Vec<Pattern>> {
+    let mut specialized = match row[0] {
+        Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+        ref head if Ctor::of(head).as_ref() == Some(ctor) => match *head {
+            Pattern::Tuple(ref fields) | Pattern::Constructor(_, ref fields) => fields.clone(),
+            _ => Vec::new(),
+        },
+        _ => return None,
+    };
+    specialized.extend_from_slice(&row[1..]);
+    Some(specialized)
+}
+
+/// Les lignes dont le premier motif est `_`, sans celui-ci
+fn default_rows(rows: &[Vec<Pattern>]) -> Vec<Vec<Pattern>> {
+    rows.iter()
+        .filter(|row| row[0] == Pattern::Wildcard)
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// Les constructeurs apparaissant en tête des lignes, sans doublons
+fn head_ctors(rows: &[Vec<Pattern>]) -> Vec<Ctor> {
+    let mut ctors = Vec::new();
+    for ctor in rows.iter().filter_map(|row| Ctor::of(&row[0])) {
+        if !ctors.contains(&ctor) {
+            ctors.push(ctor);
+        }
+    }
+    ctors
+}
+
+/// Les constructeurs du type absents des lignes, s'ils sont en nombre fini
+fn missing_ctors(rows: &[Vec<Pattern>], ty: &Ty) -> Option<Vec<Ctor>> {
+    let used = head_ctors(rows);
+    Ctor::all(ty).map(|all| all.into_iter().filter(|ctor| !used.contains(ctor)).collect())
+}
+
+/// Si une valeur acceptée par `row` n'est acceptée par aucune des lignes
+fn is_useful(rows: &[Vec<Pattern>], row: &[Pattern], tys: &[Ty]) -> bool {
+    if row.is_empty() {
+        return rows.is_empty();
+    }
+    let ty = &tys[0];
+    match Ctor::of(&row[0]) {
+        Some(ctor) => is_useful_ctor(rows, row, tys, &ctor),
+        None => match missing_ctors(rows, ty) {
+            // tous les constructeurs sont couverts: le `_` est utile s'il l'est pour l'un d'eux
+            Some(ref missing) if missing.is_empty() => Ctor::all(ty)
+                .unwrap_or_default()
+                .iter()
+                .any(|ctor| is_useful_ctor(rows, row, tys, ctor)),
+            _ => is_useful(&default_rows(rows), &row[1..], &tys[1..]),
+        },
+    }
+}
+
+fn is_useful_ctor(rows: &[Vec<Pattern>], row: &[Pattern], tys: &[Ty], ctor: &Ctor) -> bool {
+    let mut field_tys = ctor.fields(&tys[0]);
+    let arity = field_tys.len();
+    field_tys.extend_from_slice(&tys[1..]);
+    match specialize_row(row, ctor, arity) {
+        Some(row) => is_useful(&specialize(rows, ctor, arity), &row, &field_tys),
+        None => false,
+    }
+}
+
+/// Des valeurs, sous forme de motifs, qu'aucune ligne n'accepte
+/// Lorsqu'un constructeur manque, seules les valeurs sous celui-ci sont données: la liste
+/// est vide si et seulement si les lignes sont exhaustives, mais elle n'est pas complète.
+fn witnesses(rows: &[Vec<Pattern>], tys: &[Ty]) -> Vec<Vec<Pattern>> {
+    if tys.is_empty() {
+        return if rows.is_empty() { vec![Vec::new()] } else { Vec::new() };
+    }
+    let ty = &tys[0];
+
+    match missing_ctors(rows, ty) {
+        // tous les constructeurs sont couverts: les valeurs manquantes sont sous ceux-ci
+        Some(ref missing) if missing.is_empty() => {
+            let mut found = Vec::new();
+            for ctor in Ctor::all(ty).unwrap_or_default() {
+                let mut field_tys = ctor.fields(ty);
+                let arity = field_tys.len();
+                field_tys.extend_from_slice(&tys[1..]);
+                for mut witness in witnesses(&specialize(rows, &ctor, arity), &field_tys) {
+                    let rest = witness.split_off(arity);
+                    let mut rebuilt = vec![ctor.apply(witness)];
+                    rebuilt.extend(rest);
+                    found.push(rebuilt);
+                }
+            }
+            found
+        },
+        missing => {
+            let rest = witnesses(&default_rows(rows), &tys[1..]);
+            // sans motif en tête ou pour un type infini, `_` résume les valeurs manquantes
+            let heads: Vec<Pattern> = match missing {
+                Some(ref missing) if !head_ctors(rows).is_empty() => missing.iter()
+                    .map(|ctor| ctor.apply(vec![Pattern::Wildcard; ctor.fields(ty).len()]))
+                    .collect(),
+                _ => vec![Pattern::Wildcard],
+            };
+            let mut found = Vec::new();
+            for head in heads {
+                for witness in &rest {
+                    let mut rebuilt = vec![head.clone()];
+                    rebuilt.extend_from_slice(witness);
+                    found.push(rebuilt);
+                }
+            }
+            found
+        },
+    }
+}
+
+fn join(patterns: &[Pattern]) -> String {
+    patterns.iter().map(Pattern::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::Position;
+
+    fn span(line: usize) -> Span {
+        Span::new(Position::new(line, 1), Position::new(line, 10))
+    }
+
+    fn arms(patterns: Vec<Pattern>) -> Vec<Arm> {
+        patterns.into_iter()
+            .enumerate()
+            .map(|(i, pattern)| Arm { pattern, span: span(i + 2) })
+            .collect()
+    }
+
+    fn shape() -> Ty {
+        Ty::Enum(vec![
+            Variant { name: "Cercle".to_string(), fields: vec![Ty::Int] },
+            Variant { name: "Carre".to_string(), fields: vec![Ty::Int] },
+            Variant { name: "Vide".to_string(), fields: vec![] },
+        ])
+    }
+
+    fn ctor(name: &str, fields: Vec<Pattern>) -> Pattern {
+        Pattern::Constructor(name.to_string(), fields)
+    }
+
+    fn boolean(value: bool) -> Pattern {
+        Pattern::Literal(Constant::Bool(value))
+    }
+
+    #[test]
+    fn exhaustive_enum() {
+        let branches = arms(vec![
+            ctor("Cercle", vec![Pattern::Literal(Constant::Int(0))]),
+            ctor("Cercle", vec![Pattern::Wildcard]),
+            ctor("Carre", vec![Pattern::Wildcard]),
+            ctor("Vide", vec![]),
+        ]);
+        assert_eq!(Vec::<Error>::new(), check_switch(&shape(), &branches, span(1)));
+    }
+
+    #[test]
+    fn missing_constructors() {
+        let branches = arms(vec![ctor("Cercle", vec![Pattern::Literal(Constant::Int(0))])]);
+        let errors = check_switch(&shape(), &branches, span(1));
+        assert_eq!(
+            vec![Error::NonExhaustive("Carre(_), Vide".to_string(), span(1))],
+            errors
+        );
+        assert_eq!("E0108", errors[0].code());
+        assert_eq!(
+            "Le `switch` n'est pas exhaustif, motifs manquants: Carre(_), Vide",
+            errors[0].to_string()
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        let ty = Ty::Tuple(vec![Ty::Bool, Ty::Bool]);
+        let tuple = |a, b| Pattern::Tuple(vec![a, b]);
+        let branches = arms(vec![
+            tuple(boolean(true), Pattern::Wildcard),
+            tuple(Pattern::Wildcard, boolean(true)),
+        ]);
+        assert_eq!(
+            vec![Error::NonExhaustive("(false, false)".to_string(), span(1))],
+            check_switch(&ty, &branches, span(1))
+        );
+
+        let wide = Ty::Tuple(vec![Ty::Bool, Ty::Bool, Ty::Bool]);
+        let branches = arms(vec![Pattern::Tuple(vec![boolean(true); 3])]);
+        assert_eq!(
+            vec![Error::NonExhaustive("(false, _, _)".to_string(), span(1))],
+            check_switch(&wide, &branches, span(1))
+        );
+
+        let days = Ty::Enum(["Lun", "Mar", "Mer", "Jeu", "Ven"].iter()
+            .map(|name| Variant { name: name.to_string(), fields: vec![] })
+            .collect());
+        let errors = check_switch(&days, &arms(vec![ctor("Lun", vec![])]), span(1));
+        assert_eq!(
+            "Le `switch` n'est pas exhaustif, motifs manquants: Mar, Mer, Jeu, et 1 de plus",
+            errors[0].to_string()
+        );
+    }
+
+    #[test]
+    fn infinite_types() {
+        let one = Pattern::Literal(Constant::Int(1));
+        let errors = check_switch(&Ty::Int, &arms(vec![one.clone()]), span(1));
+        assert_eq!(vec![Error::NonExhaustive("_".to_string(), span(1))], errors);
+
+        let branches = arms(vec![one.clone(), Pattern::Wildcard, one]);
+        assert_eq!(
+            vec![Error::UnreachableArm(span(4))],
+            check_switch(&Ty::Int, &branches, span(1))
+        );
+    }
+
+    #[test]
+    fn unreachable_arms() {
+        let branches = arms(vec![
+            ctor("Cercle", vec![Pattern::Wildcard]),
+            ctor("Cercle", vec![Pattern::Literal(Constant::Int(3))]),
+            Pattern::Wildcard,
+            ctor("Vide", vec![]),
+        ]);
+        let errors = check_switch(&shape(), &branches, span(1));
+        assert_eq!(vec![Error::UnreachableArm(span(3)), Error::UnreachableArm(span(5))], errors);
+        assert_eq!("E0109", errors[0].code());
+        assert_eq!(span(3), errors[0].span());
+
+        let branches = arms(vec![boolean(true), boolean(false), Pattern::Wildcard]);
+        assert_eq!(
+            vec![Error::UnreachableArm(span(4))],
+            check_switch(&Ty::Bool, &branches, span(1))
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Extraction de l'interface d'un module
+//! L'interface résume les déclarations d'un module sans leur corps. Elle permet de
+//! vérifier les appels entre fichiers et de générer la documentation sans parcourir
+//! à nouveau le corps des fonctions.
+//!
+//! Une interface est conservée dans un fichier `.nbi`: une ligne d'en-tête `nbi <version>`
+//! suivie d'une signature par ligne, écrite comme dans le code source.
+use ast::{FunctionDeclaration, Type};
+
+use std::fmt;
+use std::result;
+
+/// La version du format des fichiers `.nbi`
+pub const NBI_VERSION: u32 = 1;
+
+/// Un type spécialisé pour les erreurs de lecture d'un fichier d'interface
+pub type IResult<T> = result::Result<T, Error>;
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// L'en-tête `nbi <version>` est absent ou mal formé
+    #[fail(display = "En-tête de fichier d'interface invalide: '{}'", 0)]
+    InvalidHeader(String),
+    /// Le fichier a été écrit avec une autre version du format
+    #[fail(display = "Version de fichier d'interface non supportée: {}", 0)]
+    UnsupportedVersion(u32),
+    /// Une ligne n'est pas une signature valide
+    #[fail(display = "Signature invalide à la ligne {}: '{}'", 0, 1)]
+    InvalidSignature(usize, String),
+}
+
+/// La signature d'une déclaration visible à l'extérieur d'un module
+/// Les structures et les constantes s'ajouteront ici lorsque l'AST les supportera
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeclSignature {
+    Function {
+        name: String,
+        /// Le nom et le type de chacun des paramètres
+        parameters: Vec<(String, Type)>,
+        return_type: Type,
+    },
+}
+
+impl DeclSignature {
+    /// Le nom de la déclaration
+    pub fn name(&self) -> &str {
+        match *self {
+            DeclSignature::Function { ref name, .. } => name,
+        }
+    }
+}
+
+impl<'a> From<&'a FunctionDeclaration> for DeclSignature {
+    fn from(function: &'a FunctionDeclaration) -> Self {
+        DeclSignature::Function {
+            name: function.identifier.clone(),
+            parameters: function.parameters.iter()
+                .map(|param| (param.name.clone(), param.category.clone()))
+                .collect(),
+            return_type: function.return_type.clone(),
+        }
+    }
+}
+
+impl fmt::Display for DeclSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeclSignature::Function { ref name, ref parameters, ref return_type } => {
+                write!(f, "fun {}(", name)?;
+                for (i, &(ref param, ref category)) in parameters.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", param, category)?;
+                }
+                write!(f, ") -> {}", return_type)
+            },
+        }
+    }
+}
+
+/// L'interface formée des déclarations passées en paramètre, dans leur ordre
+pub fn interface<'a, I>(functions: I) -> Vec<DeclSignature>
+    where
+        I: IntoIterator<Item = &'a FunctionDeclaration>,
+{
+    functions.into_iter().map(DeclSignature::from).collect()
+}
+
+/// Écrit l'interface au format `.nbi`
+pub fn to_nbi(signatures: &[DeclSignature]) -> String {
+    let mut out = format!("nbi {}\n", NBI_VERSION);
+    for signature in signatures {
+        out.push_str(&signature.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/// Lit une interface au format `.nbi`
+/// Les lignes vides sont ignorées
+pub fn from_nbi(source: &str) -> IResult<Vec<DeclSignature>> {
+    let mut lines = source.lines().enumerate();
+
+    let header = lines.next().map(|(_, line)| line.trim()).unwrap_or("");
+    let version = strip_prefix(header, "nbi ")
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or_else(|| Error::InvalidHeader(header.to_owned()))?;
+    if version != NBI_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    lines
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_function(line.trim())
+                .ok_or_else(|| Error::InvalidSignature(i + 1, line.to_owned()))
+        })
+        .collect()
+}
+
+/// Lit `fun nom(a: type, b: type) -> type`
+fn parse_function(line: &str) -> Option<DeclSignature> {
+    let rest = strip_prefix(line, "fun ")?;
+    let open = rest.find('(')?;
+    let close = rest.rfind(')')?;
+    let name = rest[..open].trim();
+    let return_type = strip_prefix(rest[close + 1..].trim(), "->")?.trim();
+    if name.is_empty() || return_type.is_empty() || close < open {
+        return None;
+    }
+
+    let parameters = split_parameters(&rest[open + 1..close])?
+        .into_iter()
+        .map(|param| {
+            let colon = param.find(':')?;
+            let (name, category) = (param[..colon].trim(), param[colon + 1..].trim());
+            if name.is_empty() || category.is_empty() {
+                return None;
+            }
+            Some((name.to_owned(), Type { name: category.to_owned() }))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(DeclSignature::Function {
+        name: name.to_owned(),
+        parameters,
+        return_type: Type { name: return_type.to_owned() },
+    })
+}
+
+fn strip_prefix<'a>(st: &'a str, prefix: &str) -> Option<&'a str> {
+    if st.starts_with(prefix) { Some(&st[prefix.len()..]) } else { None }
+}
+
+/// Sépare les paramètres aux virgules qui ne sont pas dans un type (`[int, 5]`, `map<a, b>`)
+/// Le `>` d'une flèche `->` ne ferme rien. Renvoie `None` si les délimiteurs ne sont pas
+/// équilibrés.
+fn split_parameters(params: &str) -> Option<Vec<&str>> {
+    if params.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut begin = 0;
+    let mut previous = '\0';
+    for (i, ch) in params.char_indices() {
+        match ch {
+            '[' | '<' | '(' => depth += 1,
+            '>' if previous == '-' => {},
+            ']' | '>' | ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                parts.push(&params[begin..i]);
+                begin = i + 1;
+            },
+            _ => {},
+        }
+        previous = ch;
+    }
+    if depth != 0 {
+        return None;
+    }
+    parts.push(&params[begin..]);
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::*;
+
+    #[test]
+    fn function_signatures() {
+        let ty = |name: &str| Type { name: name.to_string() };
+        let functions = vec![
+            FunctionDeclaration {
+                identifier: "somme".to_string(),
+                parameters: vec![
+                    Variable { name: "a".to_string(), category: ty("int") },
+                    Variable { name: "b".to_string(), category: ty("long") },
+                ],
+                body: box Block::new(vec![Statement::Return(Some(box "a".into()))]),
+                return_type: ty("long"),
+            },
+            FunctionDeclaration {
+                identifier: "main".to_string(),
+                parameters: vec![],
+                body: box Block::new(vec![]),
+                return_type: ty("void"),
+            },
+        ];
+
+        let signatures = interface(&functions);
+        let names: Vec<_> = signatures.iter().map(|sig| sig.name()).collect();
+        assert_eq!(vec!["somme", "main"], names);
+        assert_eq!(DeclSignature::Function {
+            name: "somme".to_string(),
+            parameters: vec![("a".to_string(), ty("int")), ("b".to_string(), ty("long"))],
+            return_type: ty("long"),
+        }, signatures[0]);
+        assert_eq!("fun somme(a: int, b: long) -> long", signatures[0].to_string());
+        assert_eq!("fun main() -> void", signatures[1].to_string());
+    }
+
+    #[test]
+    fn nbi_round_trip() {
+        let ty = |name: &str| Type { name: name.to_string() };
+        let signatures = vec![
+            DeclSignature::Function {
+                name: "premier".to_string(),
+                parameters: vec![
+                    ("valeurs".to_string(), ty("[int, 5]")),
+                    ("defaut".to_string(), ty("int")),
+                ],
+                return_type: ty("int"),
+            },
+            DeclSignature::Function {
+                name: "vide?".to_string(),
+                parameters: vec![],
+                return_type: ty("bool"),
+            },
+        ];
+
+        let nbi = to_nbi(&signatures);
+        assert_eq!(
+            "nbi 1\nfun premier(valeurs: [int, 5], defaut: int) -> int\nfun vide?() -> bool\n",
+            nbi
+        );
+        assert_eq!(Ok(signatures), from_nbi(&nbi));
+    }
+
+    #[test]
+    fn nbi_errors() {
+        assert_eq!(
+            Err(Error::InvalidHeader("fun f() -> int".to_string())),
+            from_nbi("fun f() -> int")
+        );
+        assert_eq!(Err(Error::UnsupportedVersion(2)), from_nbi("nbi 2\n"));
+        assert_eq!(
+            Err(Error::InvalidSignature(3, "fun g(a) -> int".to_string())),
+            from_nbi("nbi 1\nfun f() -> int\nfun g(a) -> int\n")
+        );
+        assert_eq!(Ok(vec![]), from_nbi("nbi 1\n\n"));
+        assert_eq!(
+            Err(Error::InvalidSignature(2, "fun f(a: int], b: int) -> int".to_string())),
+            from_nbi("nbi 1\nfun f(a: int], b: int) -> int\n")
+        );
+        assert!(from_nbi("nbi 1\nfun f(a: [int, b: int) -> int\n").is_err());
+    }
+
+    #[test]
+    fn arrow_in_parameter_type() {
+        assert_eq!(
+            Some(vec!["f: fun(int, int) -> int", " n: map<a, b>"]),
+            split_parameters("f: fun(int, int) -> int, n: map<a, b>")
+        );
+        assert_eq!(Some(vec!["a: x -> y", " b: int"]), split_parameters("a: x -> y, b: int"));
+        assert_eq!(None, split_parameters("a: int>, b: int"));
+    }
+}
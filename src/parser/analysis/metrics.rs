@@ -0,0 +1,144 @@
+//! Métriques de complexité des fonctions
+//! La complexité cyclomatique, la profondeur d'imbrication, la longueur et le nombre de
+//! paramètres. La longueur compte les énoncés et non les lignes, l'AST n'ayant pas de positions.
+use ast::*;
+use token::Keyword;
+
+/// Métriques calculées pour une `FunctionDeclaration`.
+/// Ces métriques sont utiles pour le linter ou pour évaluer la qualité d'un programme.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FunctionMetrics {
+    /// Le nom de la fonction
+    pub name: String,
+    /// Complexité cyclomatique (McCabe), c'est-à-dire le nombre de points de
+    /// décision plus un
+    pub cyclomatic_complexity: usize,
+    /// La profondeur maximale d'imbrication des clauses et des boucles
+    /// Une fonction sans clause ni boucle a une profondeur de 0
+    pub max_nesting_depth: usize,
+    /// Le nombre d'énoncés dans la fonction, incluant ceux des blocs imbriqués
+    /// L'AST ne contenant pas de positions, ceci représente la longueur de la fonction
+    pub statements: usize,
+    /// Le nombre de paramètres de la fonction
+    pub parameters: usize,
+}
+
+/// Calcule les métriques d'une fonction
+pub fn function_metrics(function: &FunctionDeclaration) -> FunctionMetrics {
+    let mut metrics = FunctionMetrics {
+        name: function.identifier.clone(),
+        cyclomatic_complexity: 1,
+        max_nesting_depth: 0,
+        statements: 0,
+        parameters: function.parameters.len(),
+    };
+    walk_block(&function.body, 0, &mut metrics);
+    metrics
+}
+
+/// Calcule les métriques de chacune des fonctions passées en paramètre
+pub fn metrics<'a, I>(functions: I) -> Vec<FunctionMetrics>
+    where
+        I: IntoIterator<Item = &'a FunctionDeclaration>,
+{
+    functions.into_iter().map(function_metrics).collect()
+}
+
+/// Parcours un bloc se trouvant à la profondeur `depth`
+fn walk_block(block: &Block, depth: usize, metrics: &mut FunctionMetrics) {
+    if depth > metrics.max_nesting_depth {
+        metrics.max_nesting_depth = depth;
+    }
+
+    for stmt in block.statements() {
+        metrics.statements += 1;
+        match *stmt {
+            Statement::Conditional(ref cond) => {
+                // un `else` n'ajoute pas de chemin supplémentaire
+                if cond.token != Keyword::Else {
+                    metrics.cyclomatic_complexity += 1;
+                }
+                walk_block(&cond.body, depth + 1, metrics);
+            },
+            Statement::Loop(ref looping) => {
+                metrics.cyclomatic_complexity += 1;
+                walk_block(&looping.body, depth + 1, metrics);
+            },
+            Statement::LetElse(_, ref otherwise) => {
+                metrics.cyclomatic_complexity += 1;
+                walk_block(otherwise, depth + 1, metrics);
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(name: &str) -> Variable {
+        Variable { name: name.to_string(), category: Type { name: "int".to_string() } }
+    }
+
+    fn conditional(token: Keyword, body: Vec<Statement>) -> Statement {
+        ConditionalStatement {
+            token,
+            condition: match token {
+                Keyword::Else => None,
+                _ => Some(box "x".into()),
+            },
+            body: box Block::new(body),
+        }.into()
+    }
+
+    #[test]
+    fn empty_function() {
+        let fun = FunctionDeclaration {
+            identifier: "vide".to_string(),
+            parameters: vec![],
+            body: box Block::new(vec![]),
+            return_type: Type { name: "int".to_string() },
+        };
+
+        assert_eq!(FunctionMetrics {
+            name: "vide".to_string(),
+            cyclomatic_complexity: 1,
+            max_nesting_depth: 0,
+            statements: 0,
+            parameters: 0,
+        }, function_metrics(&fun));
+    }
+
+    #[test]
+    fn nested_statements() {
+        let fun = FunctionDeclaration {
+            identifier: "imbrique".to_string(),
+            parameters: vec![int("x"), int("y")],
+            body: box Block::new(vec![
+                conditional(Keyword::If, vec![
+                    LoopStatement {
+                        token: Keyword::While,
+                        condition: Some(box "y".into()),
+                        body: box Block::new(vec![
+                            Statement::Return(None),
+                        ]),
+                    }.into(),
+                ]),
+                conditional(Keyword::Elseif, vec![]),
+                conditional(Keyword::Else, vec![
+                    Statement::Return(Some(box "x".into())),
+                ]),
+            ]),
+            return_type: Type { name: "int".to_string() },
+        };
+
+        assert_eq!(FunctionMetrics {
+            name: "imbrique".to_string(),
+            cyclomatic_complexity: 4,
+            max_nesting_depth: 2,
+            statements: 6,
+            parameters: 2,
+        }, function_metrics(&fun));
+    }
+}
@@ -0,0 +1,7 @@
+//! Analyses effectuées sur l'AST une fois celui-ci construit
+
+pub mod divergence;
+pub mod entry;
+pub mod exhaustiveness;
+pub mod interface;
+pub mod metrics;
@@ -3,13 +3,49 @@
 use token::*;
 
 use std::fmt;
-use itertools::Itertools;
+use std::fmt::Write;
+
+/// Affiche `node` dans `buffer` après l'avoir vidé
+/// La capacité du tampon est conservée: afficher plusieurs noeuds avec le même tampon
+/// n'alloue que lorsque celui-ci doit grandir.
+pub fn render_into<T: fmt::Display + ?Sized>(buffer: &mut String, node: &T) {
+    buffer.clear();
+    // écrire dans une `String` n'échoue jamais
+    let _ = write!(buffer, "{}", node);
+}
+
+/// Affiche les éléments séparés par `separator`, sans construire de `String` intermédiaire
+struct Separated<'a, T: 'a>(&'a [T], &'static str);
+
+impl<'a, T: fmt::Display> fmt::Display for Separated<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.1)?;
+            }
+            fmt::Display::fmt(item, f)?;
+        }
+        Ok(())
+    }
+}
 
 /// Un block est composé de plusieurs énoncés.
 /// En dû temps, un `Block` pourra être une expression.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Block(Vec<Statement>);
 
+impl Block {
+    /// Créé un nouveau `Block` à partir des énoncés passés en paramètre
+    pub fn new(statements: Vec<Statement>) -> Self {
+        Block(statements)
+    }
+
+    /// Les énoncés contenus dans le bloc
+    pub fn statements(&self) -> &[Statement] {
+        &self.0
+    }
+}
+
 impl fmt::Display for Block {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{{\n")?;
@@ -35,6 +71,22 @@ pub enum Statement {
     /// La valeur de retour est une `Expression` ou `None`
     Return(Option<Box<Expression>>),
     VariableDeclaration(VariableDeclaration),
+    /// `let x = valeur else { ... }`: le bloc est exécuté lorsque la valeur ne peut être liée
+    /// Le bloc doit diverger (`return`, ...) puisque `x` n'existe pas après celui-ci
+    LetElse(VariableDeclaration, Box<Block>),
+    /// Un énoncé qui n'a pu être analysé, produit lors de la récupération d'erreurs
+    /// La gamme couvre le code source ignoré
+    Error(Span),
+}
+
+impl Statement {
+    /// Si l'énoncé est un noeud d'erreur
+    pub fn is_error(&self) -> bool {
+        match *self {
+            Statement::Error(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<ConditionalStatement> for Statement {
@@ -73,6 +125,15 @@ impl fmt::Display for Statement {
                 _ => writeln!(f, "return;"),
             },
             VariableDeclaration(ref var) => writeln!(f, "{}", var),
+            LetElse(ref var, ref otherwise) => writeln!(
+                f,
+                "{keyword} {ident} = {value} else {otherwise}",
+                keyword=var.token,
+                ident=var.ident,
+                value=var.value,
+                otherwise=otherwise,
+            ),
+            Error(_) => writeln!(f, "<erreur>;"),
         }
     }
 }
@@ -90,7 +151,7 @@ pub struct ConditionalStatement {
 
 impl fmt::Display for ConditionalStatement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{token} ", token=format!("{:?}", self.token).to_lowercase())?;
+        write!(f, "{token} ", token=self.token)?;
         if let Some(ref cond) = self.condition {
             write!(f, "({}) ", cond)?;
         }
@@ -140,7 +201,7 @@ pub struct VariableDeclaration {
 impl fmt::Display for VariableDeclaration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{keyword} {ident} = {value};",
-               keyword=format!("{:?}", self.token).to_lowercase(),
+               keyword=self.token,
                ident=self.ident,
                value=self.value,
         )
@@ -166,7 +227,7 @@ impl fmt::Display for FunctionDeclaration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "fun {id}({params}) -> {return_type} {body}",
                id=self.identifier,
-               params=self.parameters.iter().join(", "),
+               params=Separated(&self.parameters, ", "),
                return_type=self.return_type,
                body=self.body
         )
@@ -193,6 +254,27 @@ pub enum Expression {
     /// L'opérateur peut donc être infixe ou suffixe.
     /// L'importance de l'opérateur change l'ordre d'évaluation.
     UnaryExpression(Box<Expression>, UnaryOperator),
+    /// Une expression qui n'a pu être analysée, produite lors de la récupération d'erreurs
+    /// La gamme couvre le code source ignoré
+    Error(Span),
+}
+
+impl Expression {
+    /// Si l'expression est un noeud d'erreur ou en contient un
+    pub fn contains_error(&self) -> bool {
+        use self::Expression::*;
+        match *self {
+            Error(_) => true,
+            Identifier(_) => false,
+            Literal(self::Literal::Array(ref elements)) => {
+                elements.iter().any(|element| element.contains_error())
+            },
+            Literal(_) => false,
+            FunCall { ref arguments, .. } => arguments.iter().any(|arg| arg.contains_error()),
+            BinaryExpression(ref lhs, _, ref rhs) => lhs.contains_error() || rhs.contains_error(),
+            UnaryExpression(ref expr, _) => expr.contains_error(),
+        }
+    }
 }
 
 impl<'a> From<&'a str> for Expression {
@@ -220,11 +302,14 @@ impl fmt::Display for Expression {
         match self {
             Identifier(st) => write!(f, "{}", st)?,
             Literal(lit) => fmt::Display::fmt(lit, f)?,
-            FunCall { target, arguments } => write!(f, "{}({})", target, arguments.iter().join(", "))?,
+            FunCall { target, arguments } => {
+                write!(f, "{}({})", target, Separated(arguments, ", "))?
+            },
             BinaryExpression(lhs, op, rhs) => {
                 write!(f, "{lhs} {op} {rhs}", lhs=lhs, op=op, rhs=rhs)?;
             },
             UnaryExpression(op, ex) => write!(f, "")?,
+            Error(_) => write!(f, "<erreur>")?,
         };
         write!(f, ")")
     }
@@ -235,15 +320,32 @@ impl fmt::Display for Expression {
 /// - Chaîne de caractères,
 /// - Array,
 /// - Booléen
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum Literal {
     /// Un tableau unidimensionnel de taille fixe contenant des éléments de même type
     Array(Vec<Box<Expression>>),
-    Number(Number),
+    /// Un nombre et, s'il provient du code source, son texte original (`0xFF`, `1_000`)
+    /// afin que le formatteur et les messages d'erreur le reproduisent tel quel
+    Number(Number, Option<String>),
     String(String),
     Boolean(bool),
 }
 
+/// Deux littéraux sont égaux s'ils ont la même valeur: le texte original d'un nombre
+/// n'est pas comparé, `0xFF` est égal à `255`
+impl PartialEq for Literal {
+    fn eq(&self, other: &Literal) -> bool {
+        use self::Literal::*;
+        match (self, other) {
+            (&Array(ref a), &Array(ref b)) => a == b,
+            (&Number(ref a, _), &Number(ref b, _)) => a == b,
+            (&String(ref a), &String(ref b)) => a == b,
+            (&Boolean(a), &Boolean(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
 impl From<Vec<Box<Expression>>> for Literal {
     fn from(val: Vec<Box<Expression>>) -> Self {
         Literal::Array(val)
@@ -252,7 +354,16 @@ impl From<Vec<Box<Expression>>> for Literal {
 
 impl From<Number> for Literal {
     fn from(val: Number) -> Self {
-        Literal::Number(val)
+        Literal::Number(val, None)
+    }
+}
+
+impl Literal {
+    /// Construit le littéral d'un lexème numérique, en conservant son texte original
+    /// Renvoie `None` si la valeur ne peut être représentée par un `long`
+    pub fn from_token(number: &::token::Number) -> Option<Self> {
+        Number::parse(number)
+            .map(|value| Literal::Number(value, Some(number.as_str().to_owned())))
     }
 }
 
@@ -280,7 +391,8 @@ impl fmt::Display for Literal {
         use ::std::fmt::{Display, Debug};
         match *self {
             Array(ref arr) => Debug::fmt(arr, f),
-            Number(ref num) => Display::fmt(num, f),
+            Number(_, Some(ref text)) => f.write_str(text),
+            Number(ref num, None) => Display::fmt(num, f),
             String(ref st) => write!(f, "{}", st),
             Boolean(ref bl) => Debug::fmt(bl, f),
         }
@@ -314,6 +426,31 @@ impl From<i64> for Number {
     }
 }
 
+impl Number {
+    /// La valeur d'un lexème numérique: un `Float` s'il a une virgule ou un exposant,
+    /// sinon un `Int` s'il tient sur 32 bits et un `Long` autrement
+    pub fn parse(number: &::token::Number) -> Option<Self> {
+        use token::Number::*;
+        let (digits, radix) = match *number {
+            Float(ref st) => {
+                let digits: String = st.chars().filter(|ch| *ch != '_').collect();
+                return digits.parse().ok().map(Number::Float);
+            },
+            Binary(ref st) => (&st[2..], 2),
+            Decimal(ref st) => (&st[..], 10),
+            Hexadecimal(ref st) => (&st[2..], 16),
+            Octal(ref st) => (&st[2..], 8),
+        };
+        let digits: String = digits.chars().filter(|ch| *ch != '_').collect();
+        let value = i64::from_str_radix(&digits, radix).ok()?;
+        if value >= i64::from(i32::min_value()) && value <= i64::from(i32::max_value()) {
+            Some(Number::Int(value as i32))
+        } else {
+            Some(Number::Long(value))
+        }
+    }
+}
+
 impl fmt::Display for Number {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Number::*;
@@ -329,6 +466,12 @@ impl fmt::Display for Number {
 /// Ces opérateurs peuvent uniquement se retrouver dans une expression "binaire".
 #[derive(Clone, Debug, PartialEq)]
 pub enum BinaryOperator {
+    /// Et bit à bit
+    BitAnd,
+    /// Ou bit à bit
+    BitOr,
+    /// Ou exclusif bit à bit
+    BitXor,
     Division,
     Equality,
     Greater,
@@ -340,13 +483,18 @@ pub enum BinaryOperator {
     Multiplication,
     NotEqual,
     Plus,
-    Power
+    Power,
+    ShiftLeft,
+    ShiftRight,
 }
 
 impl fmt::Display for BinaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::BinaryOperator::*;
         write!(f, "{}", match *self {
+            BitAnd => "&",
+            BitOr => "|",
+            BitXor => "^^",
             Division => "/",
             Equality => "==",
             Greater => ">",
@@ -359,6 +507,8 @@ impl fmt::Display for BinaryOperator {
             NotEqual => "!=",
             Plus => "+",
             Power => "^",
+            ShiftLeft => "<<",
+            ShiftRight => ">>",
         })
     }
 }
@@ -366,6 +516,10 @@ impl fmt::Display for BinaryOperator {
 /// Tout opérateur s'appliquant à un opérande
 #[derive(Clone, Debug, PartialEq)]
 pub enum UnaryOperator {
+    /// Négation bit à bit
+    BitNot,
+    /// Négation arithmétique
+    Negative,
     Not,
 }
 
@@ -373,6 +527,8 @@ impl fmt::Display for UnaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::UnaryOperator::*;
         write!(f, "{}", match *self {
+            BitNot => '~',
+            Negative => '-',
             Not => '!',
         })
     }
@@ -424,9 +580,9 @@ mod test {
             token: Keyword::Let,
             ident: Variable { name: "value".to_string(), category: Type {name: "int".to_string()} },
             value: box Expression::BinaryExpression(
-                box Expression::Literal(Literal::Number(::ast::Number::Int(5))),
+                box Expression::Literal(Literal::Number(::ast::Number::Int(5), None)),
                 BinaryOperator::Plus,
-                box Expression::Literal(Literal::Number(::ast::Number::Int(10))),
+                box Expression::Literal(Literal::Number(::ast::Number::Int(10), None)),
             ),
         };
 
@@ -462,13 +618,13 @@ return ((a) + (2));
                             name: "string".to_string(),
                         },
                     },
-                    value: box Literal::Number(1.into()).into(),
+                    value: box Literal::from(Number::Int(1)).into(),
                 }.into(),
                 Statement::Return(
                     Some(box Expression::BinaryExpression(
                         box Literal::String("a".to_string()).into(),
                         BinaryOperator::Plus,
-                        box Literal::Number(2.into()).into(),
+                        box Literal::from(Number::Int(2)).into(),
                     )),
                 ),
             ]),
@@ -479,4 +635,56 @@ return ((a) + (2));
 
         assert_eq!(expected, format!("{}", va));
     }
+
+    #[test]
+    fn error_nodes() {
+        let span = Span::new(Position::new(1, 5), Position::new(1, 9));
+        let expr = Expression::BinaryExpression(
+            box "a".into(),
+            BinaryOperator::Plus,
+            box Literal::Array(vec![box Expression::Error(span)]).into(),
+        );
+        let stmt = Statement::Expression(box Expression::Error(span));
+
+        assert!(expr.contains_error());
+        assert!(!Expression::from("a").contains_error());
+        assert!(!stmt.is_error());
+        assert!(Statement::Error(span).is_error());
+        assert_eq!("(<erreur>);\n", format!("{}", stmt));
+        assert_eq!("<erreur>;\n", format!("{}", Statement::Error(span)));
+    }
+
+    #[test]
+    fn number_literals_keep_their_text() {
+        let (tokens, errors) = ::lexer::Lexer::new("0xFF 0b1010 0o17 1_000 3_000_000_000")
+            .tokenize_all();
+        assert!(errors.is_empty());
+        let literals: Vec<Literal> = tokens.iter()
+            .filter_map(|token| match *token.token_type() {
+                TokenType::Number(ref number) => Literal::from_token(number),
+                _ => None,
+            })
+            .collect();
+
+        let values: Vec<&Number> = literals.iter()
+            .filter_map(|literal| match *literal {
+                Literal::Number(ref value, _) => Some(value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![&Number::Int(255), &Number::Int(10), &Number::Int(15), &Number::Int(1000),
+                 &Number::Long(3_000_000_000)],
+            values
+        );
+        let rendered: Vec<String> = literals.iter().map(Literal::to_string).collect();
+        assert_eq!(vec!["0xFF", "0b1010", "0o17", "1_000", "3_000_000_000"], rendered);
+        assert_eq!("255", Literal::from(Number::Int(255)).to_string());
+        assert_eq!(Literal::from(Number::Int(255)), literals[0]);
+        assert_ne!(Literal::from(Number::Int(254)), literals[0]);
+
+        let float = ::token::Number::Float("1_000.5e-3".to_string());
+        assert_eq!(Some(Number::Float(1.0005)), Number::parse(&float));
+        assert_eq!("1_000.5e-3", Literal::from_token(&float).unwrap().to_string());
+    }
 }
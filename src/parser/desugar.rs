@@ -0,0 +1,354 @@
+//! Désucrage de l'AST
+//! Réécrit les constructions de surface en un langage noyau plus petit, afin que les
+//! analyses suivantes n'aient à traiter que celui-ci:
+//! - `unless c { ... }` devient `if !c { ... }`
+//! - `if a { } elseif b { } else { }` devient `if a { } else { if b { } else { } }`
+//!
+//! Le langage noyau, vérifié par `non_core_constructs`, est formé de:
+//! - les énoncés `Assignment`, `Conditional` (`if` et `else` seulement), `Loop`,
+//!   `Expression`, `Return`, `VariableDeclaration` et `LetElse`
+//! - toutes les expressions, sauf les noeuds d'erreur
+//!
+//! L'AST ne contenant pas de positions, chaque réécriture est notée par une `Origin`
+//! contenant le chemin de l'énoncé produit. Un diagnostic sur cet énoncé peut ainsi
+//! parler du code que l'utilisateur a écrit.
+use ast::*;
+use logging::Phase;
+use token::Keyword;
+use validate;
+use visit::{self, Visitor};
+
+/// Une construction de surface ayant été réécrite
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sugar {
+    /// `unless c` réécrit en `if !c`
+    Unless,
+    /// `elseif c` réécrit en `else { if c }`
+    Elseif,
+}
+
+/// L'origine d'un énoncé produit par le désucrage
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Origin {
+    pub sugar: Sugar,
+    /// Les indices menant à l'énoncé produit: l'énoncé `path[0]` du corps de la fonction,
+    /// puis l'énoncé `path[1]` du bloc de celui-ci, etc.
+    pub path: Vec<usize>,
+}
+
+/// Désucre une fonction et renvoie les origines des énoncés réécrits
+pub fn desugar(function: &FunctionDeclaration) -> (FunctionDeclaration, Vec<Origin>) {
+    let _phase = Phase::begin("désucrage");
+    let mut origins = Vec::new();
+    let body = desugar_block(&function.body, &mut Vec::new(), &mut origins);
+    let function = FunctionDeclaration { body: box body, ..function.clone() };
+    validate::debug_assert_valid(&function);
+    debug_event!("'{}': {} réécritures", function.identifier, origins.len());
+    (function, origins)
+}
+
+fn desugar_block(block: &Block, path: &mut Vec<usize>, origins: &mut Vec<Origin>) -> Block {
+    let statements = block.statements();
+    let mut output = Vec::with_capacity(statements.len());
+    let mut i = 0;
+
+    while i < statements.len() {
+        match statements[i] {
+            Statement::Conditional(ref cond)
+                if cond.token == Keyword::If || cond.token == Keyword::Unless => {
+                // la clause s'étend sur les `elseif` et le `else` qui suivent
+                let mut chain = vec![cond];
+                while let Some(&Statement::Conditional(ref next)) = statements.get(i + chain.len())
+                {
+                    match next.token {
+                        Keyword::Elseif => chain.push(next),
+                        Keyword::Else => {
+                            chain.push(next);
+                            break;
+                        },
+                        _ => break,
+                    }
+                }
+                i += chain.len();
+
+                path.push(output.len());
+                output.extend(desugar_chain(&chain, path, origins));
+                path.pop();
+            },
+            ref stmt => {
+                path.push(output.len());
+                output.push(desugar_statement(stmt, path, origins));
+                path.pop();
+                i += 1;
+            },
+        }
+    }
+
+    Block::new(output)
+}
+
+/// Désucre une clause `if`/`unless` suivie de ses `elseif` et de son `else`
+/// `path` est le chemin du `if` produit; le `else` produit le suit directement
+fn desugar_chain(
+    chain: &[&ConditionalStatement],
+    path: &mut Vec<usize>,
+    origins: &mut Vec<Origin>,
+) -> Vec<Statement> {
+    let first = chain[0];
+    let condition = match first.token {
+        Keyword::Unless => {
+            origins.push(Origin { sugar: Sugar::Unless, path: path.clone() });
+            first.condition.as_ref().map(|cond| {
+                box Expression::UnaryExpression(cond.clone(), UnaryOperator::Not)
+            })
+        },
+        _ => first.condition.clone(),
+    };
+    let mut output = vec![Statement::Conditional(ConditionalStatement {
+        token: Keyword::If,
+        condition,
+        body: box desugar_block(&first.body, path, origins),
+    })];
+
+    // le `else` suit le `if` dans le même bloc
+    if let Some(last) = path.last_mut() {
+        *last += 1;
+    }
+    match chain.get(1) {
+        Some(next) if next.token == Keyword::Elseif => {
+            origins.push(Origin { sugar: Sugar::Elseif, path: with(path, 0) });
+            let mut inner = path.clone();
+            inner.push(0);
+            let nested = desugar_chain(&chain[1..], &mut inner, origins);
+            output.push(Statement::Conditional(ConditionalStatement {
+                token: Keyword::Else,
+                condition: None,
+                body: box Block::new(nested),
+            }));
+        },
+        Some(otherwise) => output.push(Statement::Conditional(ConditionalStatement {
+            token: Keyword::Else,
+            condition: None,
+            body: box desugar_block(&otherwise.body, path, origins),
+        })),
+        None => {},
+    }
+    if let Some(last) = path.last_mut() {
+        *last -= 1;
+    }
+
+    output
+}
+
+fn desugar_statement(
+    stmt: &Statement,
+    path: &mut Vec<usize>,
+    origins: &mut Vec<Origin>,
+) -> Statement {
+    match *stmt {
+        Statement::Conditional(ref cond) => Statement::Conditional(ConditionalStatement {
+            body: box desugar_block(&cond.body, path, origins),
+            ..cond.clone()
+        }),
+        Statement::Loop(ref looping) => Statement::Loop(LoopStatement {
+            body: box desugar_block(&looping.body, path, origins),
+            ..looping.clone()
+        }),
+        Statement::LetElse(ref decl, ref otherwise) => {
+            Statement::LetElse(decl.clone(), box desugar_block(otherwise, path, origins))
+        },
+        ref stmt => stmt.clone(),
+    }
+}
+
+/// Les constructions hors du langage noyau restant dans la fonction
+/// Après le désucrage d'un AST sans erreur, cette liste doit être vide.
+pub fn non_core_constructs(function: &FunctionDeclaration) -> Vec<&'static str> {
+    let mut checker = CoreChecker::default();
+    checker.visit_function(function);
+    checker.found
+}
+
+#[derive(Default)]
+struct CoreChecker {
+    found: Vec<&'static str>,
+}
+
+impl Visitor for CoreChecker {
+    // les correspondances sont exhaustives: une nouvelle variante doit être classée ici
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match *stmt {
+            Statement::Conditional(ref cond) => match cond.token {
+                Keyword::If | Keyword::Else => {},
+                Keyword::Unless => self.found.push("unless"),
+                Keyword::Elseif => self.found.push("elseif"),
+                _ => self.found.push("clause invalide"),
+            },
+            Statement::Error(_) => self.found.push("énoncé d'erreur"),
+            Statement::Assignment(..)
+            | Statement::Loop(_)
+            | Statement::Expression(_)
+            | Statement::Return(_)
+            | Statement::VariableDeclaration(_)
+            | Statement::LetElse(..) => {},
+        }
+        visit::walk_statement(self, stmt);
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        match *expr {
+            Expression::Error(_) => self.found.push("expression d'erreur"),
+            Expression::Identifier(_)
+            | Expression::Literal(_)
+            | Expression::FunCall { .. }
+            | Expression::BinaryExpression(..)
+            | Expression::UnaryExpression(..) => {},
+        }
+        visit::walk_expression(self, expr);
+    }
+}
+
+fn with(path: &[usize], index: usize) -> Vec<usize> {
+    let mut path = path.to_vec();
+    path.push(index);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditional(token: Keyword, body: Vec<Statement>) -> Statement {
+        ConditionalStatement {
+            token,
+            condition: match token {
+                Keyword::Else => None,
+                _ => Some(box "x".into()),
+            },
+            body: box Block::new(body),
+        }.into()
+    }
+
+    fn function(body: Vec<Statement>) -> FunctionDeclaration {
+        FunctionDeclaration {
+            identifier: "f".to_string(),
+            parameters: vec![],
+            body: box Block::new(body),
+            return_type: Type { name: "void".to_string() },
+        }
+    }
+
+    fn not_x() -> Option<Box<Expression>> {
+        Some(box Expression::UnaryExpression(box "x".into(), UnaryOperator::Not))
+    }
+
+    /// Une fonction utilisant toutes les constructions de surface
+    fn every_construct() -> FunctionDeclaration {
+        let declaration = VariableDeclaration {
+            token: Keyword::Let,
+            ident: Variable { name: "y".to_string(), category: Type { name: "int".to_string() } },
+            value: box Expression::FunCall {
+                target: "g".to_string(),
+                arguments: vec![box Literal::Array(vec![box "x".into()]).into()],
+            },
+        };
+        function(vec![
+            declaration.clone().into(),
+            Statement::LetElse(declaration.clone(), box Block::new(vec![
+                conditional(Keyword::Unless, vec![Statement::Return(None)]),
+                conditional(Keyword::Else, vec![Statement::Return(None)]),
+            ])),
+            Statement::Assignment(declaration.ident.clone(), box Expression::BinaryExpression(
+                box "y".into(),
+                BinaryOperator::Plus,
+                box Expression::UnaryExpression(box "x".into(), UnaryOperator::Not),
+            )),
+            LoopStatement {
+                token: Keyword::While,
+                condition: Some(box "x".into()),
+                body: box Block::new(vec![
+                    conditional(Keyword::If, vec![]),
+                    conditional(Keyword::Elseif, vec![]),
+                    conditional(Keyword::Elseif, vec![]),
+                ]),
+            }.into(),
+            conditional(Keyword::Unless, vec![]),
+            conditional(Keyword::Elseif, vec![Statement::Expression(box "x".into())]),
+            Statement::Return(Some(box "y".into())),
+        ])
+    }
+
+    #[test]
+    fn desugared_ast_is_core() {
+        let original = every_construct();
+        assert_eq!(
+            vec!["unless", "elseif", "elseif", "unless", "elseif"],
+            non_core_constructs(&original)
+        );
+
+        let (fun, _) = desugar(&original);
+        assert_eq!(Vec::<&str>::new(), non_core_constructs(&fun));
+    }
+
+    #[test]
+    fn error_nodes_are_not_core() {
+        let span = ::token::Span::new(::token::Position::new(1, 1), ::token::Position::new(1, 2));
+        let (fun, _) = desugar(&function(vec![
+            Statement::Error(span),
+            Statement::Expression(box Expression::Error(span)),
+        ]));
+        assert_eq!(vec!["énoncé d'erreur", "expression d'erreur"], non_core_constructs(&fun));
+    }
+
+    #[test]
+    fn unless() {
+        let (fun, origins) = desugar(&function(vec![
+            Statement::Return(None),
+            conditional(Keyword::Unless, vec![]),
+        ]));
+
+        assert_eq!(function(vec![
+            Statement::Return(None),
+            ConditionalStatement {
+                token: Keyword::If,
+                condition: not_x(),
+                body: box Block::new(vec![]),
+            }.into(),
+        ]), fun);
+        assert_eq!(vec![Origin { sugar: Sugar::Unless, path: vec![1] }], origins);
+    }
+
+    #[test]
+    fn elseif_chain() {
+        let ret = || Statement::Return(None);
+        let (fun, origins) = desugar(&function(vec![
+            conditional(Keyword::If, vec![ret()]),
+            conditional(Keyword::Elseif, vec![]),
+            conditional(Keyword::Elseif, vec![conditional(Keyword::Unless, vec![])]),
+            conditional(Keyword::Else, vec![ret()]),
+            ret(),
+        ]));
+
+        let negated = ConditionalStatement {
+            token: Keyword::If,
+            condition: not_x(),
+            body: box Block::new(vec![]),
+        };
+        assert_eq!(function(vec![
+            conditional(Keyword::If, vec![ret()]),
+            conditional(Keyword::Else, vec![
+                conditional(Keyword::If, vec![]),
+                conditional(Keyword::Else, vec![
+                    conditional(Keyword::If, vec![negated.into()]),
+                    conditional(Keyword::Else, vec![ret()]),
+                ]),
+            ]),
+            ret(),
+        ]), fun);
+        assert_eq!(vec![
+            Origin { sugar: Sugar::Elseif, path: vec![1, 0] },
+            Origin { sugar: Sugar::Elseif, path: vec![1, 1, 0] },
+            Origin { sugar: Sugar::Unless, path: vec![1, 1, 0, 0] },
+        ], origins);
+    }
+}
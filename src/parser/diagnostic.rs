@@ -0,0 +1,466 @@
+//! Affichage des diagnostics pour l'utilisateur.
+//! Un diagnostic est affiché avec la ligne du code source concernée et des
+//! marqueurs sous la gamme fautive, optionnellement en couleur (ANSI).
+use analysis::{entry, exhaustiveness};
+use lexer::{error::Error, is_newline};
+use lint::{Level, Lint, UnknownRule};
+use locale::Locale;
+use token::Span;
+use types;
+use version;
+
+use std::env;
+use std::fmt::Write;
+use std::str::FromStr;
+
+const RESET: &str = "\u{1b}[0m";
+const BOLD: &str = "\u{1b}[1m";
+const RED: &str = "\u{1b}[1;31m";
+const YELLOW: &str = "\u{1b}[1;33m";
+const BLUE: &str = "\u{1b}[1;34m";
+
+/// La gravité d'un diagnostic
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(&self, locale: Locale) -> &'static str {
+        match (*self, locale) {
+            (Severity::Warning, Locale::French) => "avertissement",
+            (Severity::Warning, Locale::English) => "warning",
+            (Severity::Error, Locale::French) => "erreur",
+            (Severity::Error, Locale::English) => "error",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match *self {
+            Severity::Warning => YELLOW,
+            Severity::Error => RED,
+        }
+    }
+}
+
+/// Un message destiné à l'utilisateur
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Le code de l'erreur, si elle en a un
+    pub code: Option<&'static str>,
+    pub message: String,
+    /// La gamme du code source concernée, si elle est connue
+    pub span: Option<Span>,
+}
+
+impl Diagnostic {
+    /// Construit un diagnostic à partir d'une erreur du lexer
+    pub fn from_lexer_error(err: &Error, locale: Locale) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: Some(err.code()),
+            message: err.message(locale),
+            span: Some(err.span()),
+        }
+    }
+
+    /// Construit un diagnostic à partir d'une fonctionnalité absente de la version demandée
+    pub fn from_version_error(err: &version::Error, locale: Locale) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: Some(err.code()),
+            message: err.message(locale),
+            span: Some(err.span()),
+        }
+    }
+
+    /// Construit un diagnostic à partir d'une erreur de type, avec le type suggéré
+    pub fn from_type_error(err: &types::Error, locale: Locale) -> Self {
+        let message = err.message(locale);
+        let message = match (err, err.suggestion(), locale) {
+            (&types::Error::UnknownType(..), Some(primitive), Locale::French) => {
+                format!("{}, vouliez-vous dire '{}'?", message, primitive)
+            },
+            (&types::Error::UnknownType(..), Some(primitive), Locale::English) => {
+                format!("{}, did you mean '{}'?", message, primitive)
+            },
+            (&types::Error::LiteralOutOfRange { .. }, Some(primitive), Locale::French) => {
+                format!("{}, utiliser '{}' ou 'double'", message, primitive)
+            },
+            (&types::Error::LiteralOutOfRange { .. }, Some(primitive), Locale::English) => {
+                format!("{}, use '{}' or 'double'", message, primitive)
+            },
+            _ => message,
+        };
+        Diagnostic { severity: Severity::Error, code: Some(err.code()), message, span: None }
+    }
+
+    /// Construit un diagnostic à partir d'une violation du linter
+    /// Renvoie `None` si la règle est désactivée
+    pub fn from_lint(lint: &Lint) -> Option<Self> {
+        let severity = match lint.level {
+            Level::Allow => return None,
+            Level::Warn => Severity::Warning,
+            Level::Deny => Severity::Error,
+        };
+        Some(Diagnostic {
+            severity,
+            code: Some(lint.rule),
+            message: lint.message.clone(),
+            span: None,
+        })
+    }
+}
+
+impl<'a> From<&'a UnknownRule> for Diagnostic {
+    /// Une règle inconnue demandée par l'utilisateur est un avertissement
+    fn from(unknown: &'a UnknownRule) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: None,
+            message: unknown.to_string(),
+            span: None,
+        }
+    }
+}
+
+impl<'a> From<&'a entry::Error> for Diagnostic {
+    /// Un point d'entrée invalide empêche la compilation d'un exécutable
+    fn from(err: &'a entry::Error) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: Some(err.code()),
+            message: err.to_string(),
+            span: None,
+        }
+    }
+}
+
+impl<'a> From<&'a exhaustiveness::Error> for Diagnostic {
+    fn from(err: &'a exhaustiveness::Error) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: Some(err.code()),
+            message: err.to_string(),
+            span: Some(err.span()),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Le diagnostic sous forme d'un objet JSON sur une seule ligne, pour
+    /// `--message-format=json`. Les outils peuvent ainsi lire un objet par ligne.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"type\":\"diagnostic\",\"severity\":");
+        write_json_string(&mut out, self.severity.label(Locale::English));
+        out.push_str(",\"code\":");
+        match self.code {
+            Some(code) => write_json_string(&mut out, code),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"message\":");
+        write_json_string(&mut out, &self.message);
+        out.push_str(",\"span\":");
+        match self.span {
+            Some(span) => {
+                let (begin, end) = (span.begin(), span.end());
+                let _ = write!(
+                    out,
+                    "{{\"begin\":{{\"line\":{},\"column\":{}}},",
+                    begin.line(), begin.column(),
+                );
+                let _ = write!(
+                    out,
+                    "\"end\":{{\"line\":{},\"column\":{}}}}}",
+                    end.line(), end.column(),
+                );
+            },
+            None => out.push_str("null"),
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Écrit une chaîne JSON, guillemets inclus
+fn write_json_string(out: &mut String, st: &str) {
+    out.push('"');
+    for ch in st.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            },
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+/// Le format des messages du compilateur (`--message-format=human|json`)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageFormat {
+    /// Texte destiné à l'utilisateur, voir `Renderer`
+    Human,
+    /// Un objet JSON par ligne, voir `Diagnostic::to_json`
+    Json,
+}
+
+impl Default for MessageFormat {
+    fn default() -> Self {
+        MessageFormat::Human
+    }
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(st: &str) -> Result<Self, Self::Err> {
+        match st {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!("Valeur invalide pour --message-format: '{}' (human ou json)", st)),
+        }
+    }
+}
+
+/// Le choix de l'utilisateur quant aux couleurs (`--color=auto|always|never`)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Utilise les couleurs si la sortie est un terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Détermine si les couleurs doivent être utilisées
+    /// `is_terminal` indique si la sortie est un terminal, ce que seul l'appelant peut savoir.
+    /// En mode `Auto`, les variables d'environnement `NO_COLOR` et `TERM=dumb` désactivent
+    /// aussi les couleurs.
+    pub fn use_color(&self, is_terminal: bool) -> bool {
+        match *self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                is_terminal
+                    && env::var_os("NO_COLOR").is_none()
+                    && env::var("TERM").map(|term| term != "dumb").unwrap_or(true)
+            },
+        }
+    }
+}
+
+impl Default for ColorChoice {
+    fn default() -> Self {
+        ColorChoice::Auto
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(st: &str) -> Result<Self, Self::Err> {
+        match st {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("Valeur invalide pour --color: '{}' (auto, always ou never)", st)),
+        }
+    }
+}
+
+/// Affiche les diagnostics sous forme de texte
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Renderer {
+    /// Si les codes de couleur ANSI sont émis
+    pub color: bool,
+    /// La langue des étiquettes (`erreur`, `avertissement`)
+    pub locale: Locale,
+}
+
+impl Renderer {
+    pub fn new(color: bool, locale: Locale) -> Self {
+        Renderer { color, locale }
+    }
+
+    /// Affiche le diagnostic avec la ligne du code source concernée
+    pub fn render(&self, diagnostic: &Diagnostic, source: &str) -> String {
+        let mut out = String::new();
+        let severity_color = diagnostic.severity.color();
+
+        out.push_str(&self.paint(severity_color, diagnostic.severity.label(self.locale)));
+        if let Some(code) = diagnostic.code {
+            out.push_str(&self.paint(severity_color, &format!("[{}]", code)));
+        }
+        out.push_str(&self.paint(BOLD, &format!(": {}", diagnostic.message)));
+        out.push('\n');
+
+        let span = match diagnostic.span {
+            Some(span) => span,
+            None => return out,
+        };
+        let begin = span.begin();
+        let line = match source_line(source, begin.line()) {
+            Some(line) => line,
+            None => return out,
+        };
+
+        // les marqueurs s'arrêtent à la fin de la ligne si la gamme en couvre plusieurs
+        let line_len = line.chars().count();
+        let last = if span.end().line() == begin.line() { span.end().column() } else { line_len };
+        let markers = last.saturating_sub(begin.column()) + 1;
+
+        let number = begin.line().to_string();
+        let gutter = " ".repeat(number.len());
+        let _ = writeln!(out, "{}{} {}", gutter, self.paint(BLUE, "-->"), begin);
+        let _ = writeln!(out, "{} {}", gutter, self.paint(BLUE, "|"));
+        let _ = writeln!(out, "{} {}", self.paint(BLUE, &format!("{} |", number)), line);
+        let _ = writeln!(
+            out,
+            "{} {} {}{}",
+            gutter,
+            self.paint(BLUE, "|"),
+            " ".repeat(begin.column().saturating_sub(1)),
+            self.paint(severity_color, &"^".repeat(markers)),
+        );
+        out
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        }
+        else {
+            text.to_owned()
+        }
+    }
+}
+
+/// Renvoie le texte de la ligne `line` (commençant à 1), sans la fin de ligne
+/// Les lignes sont séparées de la même façon que dans le lexer
+fn source_line(source: &str, line: usize) -> Option<&str> {
+    let mut current = 1;
+    let mut start = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        if !is_newline(&ch) {
+            continue;
+        }
+        if current == line {
+            return Some(&source[start..idx]);
+        }
+        // une séquence CRLF ne compte que pour une fin de ligne
+        if ch == '\u{000D}' && chars.peek().map(|&(_, next)| next) == Some('\u{000A}') {
+            chars.next();
+        }
+        current += 1;
+        start = chars.peek().map(|&(idx, _)| idx).unwrap_or(source.len());
+    }
+    if current == line { Some(&source[start..]) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::Position;
+
+    #[test]
+    fn render_plain() {
+        let source = "let a = 1;\r\nlet b = \"abc\n";
+        let err = Error::UnterminatedString(Position::new(2, 12));
+        let diagnostic = Diagnostic::from_lexer_error(&err, Locale::English);
+
+        assert_eq!("\
+error[E0007]: Unterminated string at 2:12
+ --> 2:12
+  |
+2 | let b = \"abc
+  |            ^
+", Renderer::new(false, Locale::English).render(&diagnostic, source));
+    }
+
+    #[test]
+    fn localized_version_error() {
+        use token::Span;
+        use version::{Feature, LanguageVersion};
+
+        let span = Span::new(Position::new(1, 1), Position::new(1, 6));
+        let err = LanguageVersion::V0_1.require(Feature::Switch, span).unwrap_err();
+        let diagnostic = Diagnostic::from_version_error(&err, Locale::English);
+
+        assert_eq!(Some("E0110"), diagnostic.code);
+        assert_eq!(Some(span), diagnostic.span);
+        assert_eq!(
+            "error[E0110]: switch requires language version 0.2 (0.1 requested)",
+            Renderer::new(false, Locale::English).render(&diagnostic, "").lines().next().unwrap()
+        );
+    }
+
+    #[test]
+    fn localized_type_error() {
+        let err = types::Registry::default().resolve("strng").unwrap_err();
+        assert_eq!(
+            "Type inconnu: 'strng', vouliez-vous dire 'string'?",
+            Diagnostic::from_type_error(&err, Locale::French).message
+        );
+        assert_eq!(
+            "Unknown type: 'strng', did you mean 'string'?",
+            Diagnostic::from_type_error(&err, Locale::English).message
+        );
+    }
+
+    #[test]
+    fn json() {
+        let err = Error::InvalidString("\"a\tb".to_string(), Position::new(1, 3));
+        let diagnostic = Diagnostic::from_lexer_error(&err, Locale::English);
+
+        assert_eq!(
+            "{\"type\":\"diagnostic\",\"severity\":\"error\",\"code\":\"E0003\",\
+             \"message\":\"Invalid string: '\\\"a\\tb' at 1:3\",\
+             \"span\":{\"begin\":{\"line\":1,\"column\":3},\"end\":{\"line\":1,\"column\":3}}}",
+            diagnostic.to_json()
+        );
+
+        let unknown = Diagnostic::from(&UnknownRule("x\u{1}".to_string()));
+        assert!(unknown.to_json().contains("\"code\":null"));
+        assert!(unknown.to_json().contains("x\\u0001"));
+        assert!(unknown.to_json().ends_with("\"span\":null}"));
+        assert_eq!(Ok(MessageFormat::Json), "json".parse());
+        assert!("xml".parse::<MessageFormat>().is_err());
+    }
+
+    #[test]
+    fn render_color() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            code: None,
+            message: "attention".to_string(),
+            span: Some(Span::new(Position::new(1, 5), Position::new(1, 7))),
+        };
+
+        assert_eq!(format!("\
+{y}avertissement{r}{b}: attention{r}
+ {bl}-->{r} 1:5
+  {bl}|{r}
+{bl}1 |{r} let abc = 1;
+  {bl}|{r}     {y}^^^{r}
+", y = YELLOW, r = RESET, b = BOLD, bl = BLUE),
+            Renderer::new(true, Locale::French).render(&diagnostic, "let abc = 1;"));
+    }
+
+    #[test]
+    fn color_choice() {
+        assert_eq!(Ok(ColorChoice::Never), "never".parse());
+        assert!("parfois".parse::<ColorChoice>().is_err());
+        assert!(ColorChoice::Always.use_color(false));
+        assert!(!ColorChoice::Never.use_color(true));
+        assert!(!ColorChoice::Auto.use_color(false));
+    }
+}
@@ -0,0 +1,154 @@
+//! Application de corrections textuelles sur le code source.
+//! Les corrections proviennent du linter ou des diagnostics et sont exprimées
+//! avec les `Span` des lexèmes, c'est-à-dire en lignes et colonnes.
+use lexer::is_newline;
+use token::{Position, Span};
+
+use std::result;
+
+/// Un type spécialisé pour les erreurs d'application de corrections
+pub type EResult<T> = result::Result<T, Error>;
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// La position n'existe pas dans le code source
+    #[fail(display = "Position invalide dans le code source: {}", 0)]
+    InvalidPosition(Position),
+    /// La fin de la gamme est avant son début
+    #[fail(display = "Gamme invalide: {}", 0)]
+    InvalidSpan(Span),
+    /// Deux corrections modifient le même texte
+    #[fail(display = "Les corrections à {} et {} se chevauchent", 0, 1)]
+    Overlap(Span, Span),
+}
+
+/// Une correction textuelle: remplace le texte de `span` par `replacement`
+/// La fin du `span` est inclusive, tout comme pour les lexèmes
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new<S: Into<String>>(span: Span, replacement: S) -> Self {
+        TextEdit { span, replacement: replacement.into() }
+    }
+}
+
+/// Applique les corrections au code source et renvoie le nouveau texte
+/// Les corrections peuvent être dans n'importe quel ordre, mais ne doivent pas se chevaucher.
+/// Le code source n'est pas modifié si une erreur est renvoyée.
+pub fn apply(source: &str, edits: &[TextEdit]) -> EResult<String> {
+    let chars = char_positions(source);
+    let find = |pos: Position| {
+        chars
+            .binary_search_by(|&(other, _, _)| other.cmp(&pos))
+            .map(|idx| chars[idx])
+            .map_err(|_| Error::InvalidPosition(pos))
+    };
+
+    let mut ranges = Vec::with_capacity(edits.len());
+    for edit in edits {
+        if edit.span.end() < edit.span.begin() {
+            return Err(Error::InvalidSpan(edit.span));
+        }
+        let (_, start, _) = find(edit.span.begin())?;
+        let (_, last, last_len) = find(edit.span.end())?;
+        ranges.push((start, last + last_len, edit));
+    }
+    ranges.sort_by_key(|&(start, end, _)| (start, end));
+
+    for pair in ranges.windows(2) {
+        let (_, previous_end, previous) = pair[0];
+        let (next_start, _, next) = pair[1];
+        if next_start < previous_end {
+            return Err(Error::Overlap(previous.span, next.span));
+        }
+    }
+
+    let mut output = String::with_capacity(source.len());
+    let mut copied = 0;
+    for (start, end, edit) in ranges {
+        debug_assert!(source.is_char_boundary(start) && source.is_char_boundary(end));
+        output.push_str(&source[copied..start]);
+        output.push_str(&edit.replacement);
+        copied = end;
+    }
+    output.push_str(&source[copied..]);
+    Ok(output)
+}
+
+/// Calcule la position, le byte de début et la longueur en bytes de chaque caractère
+/// Les lignes et colonnes sont calculées de la même façon que le lexer
+fn char_positions(source: &str) -> Vec<(Position, usize, usize)> {
+    let mut positions = Vec::with_capacity(source.len());
+    let mut position = Position::new(1, 0);
+    let mut previous: Option<char> = None;
+    for (idx, ch) in source.char_indices() {
+        if let Some(previous) = previous {
+            // si nous n'avons pas une séquence CRLF
+            if is_newline(&previous) && !(previous == '\u{000D}' && ch == '\u{000A}') {
+                position.line += 1;
+                position.column = 0;
+            }
+        }
+        position.column += 1;
+        positions.push((position, idx, ch.len_utf8()));
+        previous = Some(ch);
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(begin: (usize, usize), end: (usize, usize), replacement: &str) -> TextEdit {
+        let span = Span::new(Position::new(begin.0, begin.1), Position::new(end.0, end.1));
+        TextEdit::new(span, replacement)
+    }
+
+    #[test]
+    fn apply_unordered() {
+        let source = "let monNombre = 5;\r\nreturn monNombre;";
+        let edits = [
+            edit((2, 8), (2, 16), "mon_nombre"),
+            edit((1, 5), (1, 13), "mon_nombre"),
+        ];
+
+        assert_eq!(
+            Ok("let mon_nombre = 5;\r\nreturn mon_nombre;".to_string()),
+            apply(source, &edits)
+        );
+    }
+
+    #[test]
+    fn apply_multibyte() {
+        let source = "let été = \"à\";";
+        let edits = [edit((1, 5), (1, 7), "ete"), edit((1, 12), (1, 12), "a")];
+
+        assert_eq!(Ok("let ete = \"a\";".to_string()), apply(source, &edits));
+    }
+
+    #[test]
+    fn apply_errors() {
+        let source = "let x = 5;";
+
+        assert_eq!(
+            Err(Error::Overlap(
+                Span::new(Position::new(1, 1), Position::new(1, 5)),
+                Span::new(Position::new(1, 5), Position::new(1, 5)),
+            )),
+            apply(source, &[edit((1, 5), (1, 5), "y"), edit((1, 1), (1, 5), "const y")])
+        );
+        assert_eq!(
+            Err(Error::InvalidPosition(Position::new(2, 1))),
+            apply(source, &[edit((2, 1), (2, 1), "")])
+        );
+        assert_eq!(
+            Err(Error::InvalidSpan(Span::new(Position::new(1, 5), Position::new(1, 1)))),
+            apply(source, &[edit((1, 5), (1, 1), "")])
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use token::Span;
+
+/// Information se trouvant au début d'un fichier, avant le premier lexème:
+/// un shebang optionnel (`#!/usr/bin/env nbc`) suivi de directives `#![...]`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FileAttributes {
+    /// La ligne du shebang au complet, incluant `#!`
+    pub shebang: Option<String>,
+    /// Les directives dans l'ordre où elles apparaissent
+    pub pragmas: Vec<Pragma>,
+}
+
+/// Une directive de fichier de la forme `#![nom(argument, argument)]`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pragma {
+    pub name: String,
+    pub arguments: Vec<String>,
+    pub location: Span,
+}
+
+impl Pragma {
+    /// Analyse le contenu entre `#![` et `]`
+    /// Renvoie `None` si le contenu n'est pas de la forme `nom` ou `nom(arguments)`
+    pub(crate) fn parse(content: &str, location: Span) -> Option<Self> {
+        let content = content.trim();
+        let (name, arguments) = match content.find('(') {
+            Some(idx) => {
+                let args = content[idx + 1..].trim_right();
+                if !args.ends_with(')') {
+                    return None;
+                }
+                let arguments = args[..args.len() - 1]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|arg| !arg.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+                (content[..idx].trim_right(), arguments)
+            },
+            None => (content, Vec::new()),
+        };
+
+        if name.is_empty() || !name.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+            return None;
+        }
+
+        Some(Pragma { name: name.to_owned(), arguments, location })
+    }
+}
@@ -1,4 +1,6 @@
 use lexer::Position;
+use locale::Locale;
+use token::Span;
 
 use std::result;
 
@@ -8,9 +10,18 @@ pub type LResult<T> = result::Result<T, Error>;
 // FIXME(Nicolas): Me remplir d'encore plus d'erreurs
 #[derive(Debug, Eq, Fail, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Error {
+    /// Séquence d'échappement inconnue ou mal formée dans une chaîne de caractères
+    #[fail(display = "Séquence d'échappement invalide: '{}' à {}", 0, 1)]
+    InvalidEscape(String, Span),
     /// Identifiant invalide
     #[fail(display = "Identifiant invalide: '{}' à {}", 0, 1)]
     InvalidIdentifier(String, Position),
+    /// Directive de fichier `#![...]` mal formée
+    #[fail(display = "Directive invalide: '{}' à {}", 0, 1)]
+    InvalidPragma(String, Position),
+    /// Caractère NUL rencontré hors d'une chaîne de caractères
+    #[fail(display = "Caractère NUL inattendu à {}", 0)]
+    NulCharacter(Position),
     /// Une chaîne de caractère invalide dans l'entrée
     #[fail(display = "Chaîne de caractères invalide: '{}' à {}", 0, 1)]
     InvalidString(String, Position),
@@ -31,3 +42,93 @@ pub enum Error {
     #[fail(display = "Chaîne de caractères n'est pas terminée à {}", 0)]
     UnterminatedString(Position),
 }
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        use self::Error::*;
+        match *self {
+            InvalidIdentifier(..) => "E0001",
+            InvalidPragma(..) => "E0002",
+            InvalidString(..) => "E0003",
+            MissingStringBeginning(..) => "E0004",
+            UnexpectedEOF(..) => "E0005",
+            UnexpectedSymbol { .. } => "E0006",
+            UnterminatedString(..) => "E0007",
+            NulCharacter(..) => "E0008",
+            InvalidEscape(..) => "E0009",
+        }
+    }
+
+    /// La position où l'erreur s'est produite
+    pub fn position(&self) -> Position {
+        use self::Error::*;
+        match *self {
+            InvalidIdentifier(_, pos)
+            | InvalidPragma(_, pos)
+            | InvalidString(_, pos)
+            | MissingStringBeginning(pos)
+            | NulCharacter(pos)
+            | UnexpectedEOF(pos)
+            | UnexpectedSymbol { pos, .. }
+            | UnterminatedString(pos) => pos,
+            InvalidEscape(_, span) => span.begin(),
+        }
+    }
+
+    /// La gamme fautive, d'un seul caractère pour les erreurs n'ayant qu'une position
+    pub fn span(&self) -> Span {
+        match *self {
+            Error::InvalidEscape(_, span) => span,
+            _ => Span::new(self.position(), self.position()),
+        }
+    }
+
+    /// Le message de l'erreur dans la langue demandée
+    /// En français, le message est identique à celui de `Display`
+    pub fn message(&self, locale: Locale) -> String {
+        use self::Error::*;
+        match locale {
+            Locale::French => self.to_string(),
+            Locale::English => match *self {
+                InvalidIdentifier(ref ident, pos) => {
+                    format!("Invalid identifier: '{}' at {}", ident, pos)
+                },
+                InvalidEscape(ref sequence, span) => {
+                    format!("Invalid escape sequence: '{}' at {}", sequence, span)
+                },
+                InvalidPragma(ref pragma, pos) => {
+                    format!("Invalid directive: '{}' at {}", pragma, pos)
+                },
+                InvalidString(ref st, pos) => format!("Invalid string: '{}' at {}", st, pos),
+                MissingStringBeginning(pos) => format!("Missing start of string at {}", pos),
+                NulCharacter(pos) => format!("Unexpected NUL character at {}", pos),
+                UnexpectedEOF(pos) => {
+                    format!("End-of-File reached before the end of the expected sequence at {}", pos)
+                },
+                UnexpectedSymbol { exp, unexp, pos } => {
+                    format!("Unexpected character: '{}' instead of '{}' at {}", unexp, exp, pos)
+                },
+                UnterminatedString(pos) => format!("Unterminated string at {}", pos),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_messages() {
+        let err = Error::UnterminatedString(Position::new(3, 7));
+
+        assert_eq!("E0007", err.code());
+        assert_eq!(Position::new(3, 7), err.position());
+        assert_eq!("Chaîne de caractères n'est pas terminée à 3:7", err.message(Locale::French));
+        assert_eq!("Unterminated string at 3:7", err.message(Locale::English));
+        assert_eq!(Some(Locale::English), Locale::from_tag("en_US"));
+        assert_eq!(Some(Locale::French), Locale::from_tag("fr-CA"));
+        assert_eq!(None, Locale::from_tag("de"));
+    }
+}
@@ -1,14 +1,16 @@
+use self::attributes::{FileAttributes, Pragma};
 use self::error::{Error, LResult};
+use logging::Phase;
 use token::*;
 
-use itertools::Itertools;
-
-use std::iter::Peekable;
+use std::iter::{Enumerate, Peekable};
 use std::result;
 use std::str::Chars;
 use std::vec::Vec;
 
+pub mod attributes;
 pub mod error;
+mod operators;
 
 /// Le Lexer est un wrapper sur un itérateur qui lit les caractères pour former des
 /// lexèmes.
@@ -20,8 +22,19 @@ pub struct Lexer<'a> {
     current_char: Option<char>,
     /// Lexème courant dans le vecteur de token
     current_token: Option<Token>,
+    /// Le shebang et les directives lus au début du fichier
+    attributes: FileAttributes,
+    /// Si l'en-tête du fichier a déjà été lu
+    header_read: bool,
+    /// Si les espaces-blancs sont émis comme lexèmes `TokenType::Whitespace`
+    trivia: bool,
+    /// Les mot-clés reconnus
+    keywords: KeywordSet,
+    /// Les mot-clés demandés par `set_keyword_set`, rétablis par `reset`
+    configured_keywords: KeywordSet,
     /// L'entrée à parse, une séquence de caractères itérable
-    input: Peekable<Chars<'a>>,
+    /// `Chars::as_str` donne accès au reste de l'entrée pour les lectures octet par octet
+    input: Chars<'a>,
     /// Position actuelle dans le programme
     /// `line` est incrémenté chaque fois qu'un caractère de newline est rencontré
     /// en prenant en considération le fait que certains systèmes d'exploitation
@@ -38,13 +51,118 @@ impl<'a> Lexer<'a> {
         let mut lexer = Lexer {
             current_char: None,
             current_token: None,
-            input: input.into().chars().peekable(),
+            attributes: FileAttributes::default(),
+            header_read: false,
+            trivia: false,
+            keywords: KeywordSet::default(),
+            configured_keywords: KeywordSet::default(),
+            input: "".chars(),
             position: Position { column: 0, line: 1 },
         };
-        lexer.read(); // avance au premier caractère
+        lexer.reset(input);
         lexer
     }
 
+    /// Recommence la lecture depuis le début d'une nouvelle entrée
+    /// Les options (`emit_trivia`, `set_keyword_set`) sont conservées, mais pas les
+    /// directives du fichier précédent. Permet aux outils qui lisent de nombreux fichiers
+    /// (serveur de langage, mode surveillance) de réutiliser le même Lexer.
+    pub fn reset<S>(&mut self, input: S)
+        where
+            S: Into<&'a str>,
+    {
+        // le BOM UTF-8 n'a pas de signification et ne compte pas dans les positions
+        let input = input.into();
+        let input = if input.starts_with('\u{FEFF}') {
+            &input['\u{FEFF}'.len_utf8()..]
+        }
+        else {
+            input
+        };
+
+        self.current_char = None;
+        self.current_token = None;
+        self.attributes = FileAttributes::default();
+        self.header_read = false;
+        self.keywords = self.configured_keywords;
+        self.input = input.chars();
+        self.position = Position { column: 0, line: 1 };
+        self.read(); // avance au premier caractère
+    }
+
+    /// Lit tous les lexèmes de `source` et les passe un à un à `sink`, sans les accumuler
+    /// Le lexème `EOF` n'est pas passé. La lecture s'arrête à la première erreur.
+    pub fn tokenize_with<F>(source: &'a str, mut sink: F) -> LResult<()>
+        where
+            F: FnMut(Token),
+    {
+        let mut lexer = Lexer::new(source);
+        loop {
+            let token = lexer.read_token()?;
+            if *token.token_type() == TokenType::EOF {
+                return Ok(());
+            }
+            sink(token);
+        }
+    }
+
+    /// Comme `tokenize_with`, mais passe les lexèmes par groupes d'au plus `batch_size`
+    /// Le même vecteur est réutilisé pour chaque groupe. Les lexèmes lus avant une
+    /// erreur sont passés avant que l'erreur soit renvoyée.
+    pub fn tokenize_batches<F>(source: &'a str, batch_size: usize, mut sink: F) -> LResult<()>
+        where
+            F: FnMut(&[Token]),
+    {
+        let batch_size = batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let result = Lexer::tokenize_with(source, |token| {
+            batch.push(token);
+            if batch.len() == batch_size {
+                sink(&batch);
+                batch.clear();
+            }
+        });
+        if !batch.is_empty() {
+            sink(&batch);
+        }
+        result
+    }
+
+    /// Lit tous les lexèmes restants sans s'arrêter aux erreurs
+    /// Chaque erreur est remplacée par un lexème `TokenType::Illegal` contenant le caractère
+    /// fautif, puis ce caractère est sauté. Pour une chaîne invalide, la lecture reprend
+    /// après le guillemet fermant ou à la fin de la ligne. Le lexème `EOF` n'est pas inclus.
+    pub fn tokenize_all(&mut self) -> (Vec<Token>, Vec<Error>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        self.tokenize_all_into(&mut tokens, &mut errors);
+        (tokens, errors)
+    }
+
+    /// Comme `tokenize_all`, mais dans des vecteurs fournis qui sont d'abord vidés
+    /// Avec `reset`, la capacité des vecteurs est réutilisée d'une entrée à l'autre.
+    pub fn tokenize_all_into(&mut self, tokens: &mut Vec<Token>, errors: &mut Vec<Error>) {
+        let mut phase = Phase::begin("lexer");
+        tokens.clear();
+        errors.clear();
+        loop {
+            match self.read_token() {
+                Ok(ref token) if *token.token_type() == TokenType::EOF => {
+                    phase.set_items(tokens.len());
+                    break;
+                },
+                Ok(token) => tokens.push(token),
+                Err(error) => {
+                    debug_event!("récupération après l'erreur {:?}: caractère sauté", error);
+                    let illegal = self.current_char.map(|ch| ch.to_string()).unwrap_or_default();
+                    tokens.push(Token::new(TokenType::Illegal(illegal), error.position().into()));
+                    self.recover(&error);
+                    errors.push(error);
+                },
+            }
+        }
+    }
+
     /// Construit le prochain `token::Token` et le renvoie
     /// Renvoie `None` si la fin de la séquence est atteint
     /// Validation minimale se fait ici, c'est-à-dire que les nombres ne sont pas validés
@@ -52,78 +170,34 @@ impl<'a> Lexer<'a> {
     pub fn read_token(&mut self) -> LResult<Token> {
         use token::{TokenType::*, Keyword::{self, *}, Boolean::*, Number::*};
 
-        // saute les espaces blancs
-        // TODO: M'enlever une fois que le bug avec skip_whitespace sera résolu
-        loop {
-            match self.current_char {
-                Some(ch) if ch.is_whitespace() => self.read(),
-                _ => break,
-            };
+        if !self.header_read {
+            self.header_read = true;
+            self.read_file_header()?;
+        }
+
+        if !self.trivia {
+            self.skip_whitespace();
         }
 
         let result = match self.current_char {
             None => token!(EOF, self.position),
             Some(ch) => match ch {
-                '+' => token!(Plus, self.position),
-                '-' => token!(Minus, self.position),
-                '%' => token!(Modulo, self.position),
-                '^' => token!(Power, self.position),
-                '/' => match self.peek() {
-                    Some(&ch) if ch == '*' => { // commentaire
-                        let begin = self.position;
-                        let st = self.read_comment();
-                        token!(Comment(st), begin => self.position)
-                    },
-                    _ => token!(Division, self.position),
-                },
-                '=' => match self.peek() {
-                    Some(&ch) if ch == '=' => { // EqEq
-                        let begin = self.position;
-                        self.read();
-                        token!(EqEq, begin => self.position)
-                    },
-                    _ => token!(Eq, self.position),
-                },
-                '!' => match self.peek() {
-                    Some(&ch) if ch == '=' => { // non égal
-                        let begin = self.position;
-                        self.read();
-                        token!(NotEq, begin => self.position)
-                    },
-                    _ => token!(Not, self.position),
-                },
-                '<' => match self.peek() {
-                    Some(&ch) if ch == '=' => { // plus petit que ou égal
-                        let begin = self.position;
-                        self.read();
-                        token!(LtEq, begin => self.position)
-                    },
-                    _ => token!(Lt, self.position)
-                },
-                '>' => match self.peek() {
-                    Some(&ch) if ch == '=' => { // plus grand que ou égal
-                        let begin = self.position;
-                        self.read();
-                        token!(GtEq, begin => self.position)
-                    },
-                    _ => token!(Gt, self.position)
+                ch if ch.is_whitespace() => { // seulement lorsque les trivia sont émis
+                    let begin = self.position;
+                    let st = self.read_whitespace();
+                    token!(Whitespace(st), begin => self.position)
                 },
-                '|' => match self.peek() {
-                    Some(&ch) if ch == '|' => { // ou ou
-                        let begin = self.position;
-                        self.read();
-                        token!(OrOr, begin => self.position)
-                    },
-                    _ => token!(Or, self.position),
+                '/' if self.peek() == Some('*') => { // commentaire
+                    let begin = self.position;
+                    let st = self.read_comment()?;
+                    token!(Comment(st), begin => self.position)
                 },
-                '&' => match self.peek() {
-                    Some(&ch) if ch == '&' => { // et et
-                        let begin = self.position;
-                        self.read();
-                        token!(AndAnd, begin => self.position)
-                    },
-                    _ => token!(And, self.position),
+                '/' if self.peek() == Some('/') => { // commentaire de ligne
+                    let begin = self.position;
+                    let st = self.read_line_comment();
+                    token!(Comment(st), begin => self.position)
                 },
+                ch if operators::is_operator_start(ch) => self.read_operator(ch),
                 ',' => token!(Comma, self.position),
                 ':' => token!(Colon, self.position),
                 ';' => token!(Semicolon, self.position),
@@ -134,10 +208,11 @@ impl<'a> Lexer<'a> {
                 '[' => token!(Lbracket, self.position),
                 ']' => token!(Rbracket, self.position),
                 '_' => token!(Underscore, self.position),
+                '\0' => return Err(Error::NulCharacter(self.position)), // non consommé
                 '"' => {
                     let begin = self.position;
                     let st = self.read_string()?;
-                    token!(Literal(st), begin => self.position)
+                    token!(Literal(decode_escapes(&st, begin)?), begin => self.position)
                 },
                 ch if ch.is_alphabetic() => { // identifiant ou keyword
                     let begin = self.position;
@@ -150,7 +225,7 @@ impl<'a> Lexer<'a> {
                         token!(False, begin => self.position)
                     }
                     else {
-                        match Keyword::lookup(ident.as_ref()) {
+                        match Keyword::lookup_in(ident.as_ref(), self.keywords) {
                             Some(token) => token!(token, begin => self.position),
                             None => token!(Identifier(ident), begin => self.position),
                         }
@@ -159,28 +234,31 @@ impl<'a> Lexer<'a> {
                 ch if is_digit(&ch) => { // lit un nombre décimal/octal/etc.
                     let begin = self.position;
                     match (ch, self.peek()) {
-                        ('0', Some(&peeked)) => match &peeked {
+                        ('0', Some(peeked)) => match &peeked {
                             'b' => { // binaire
                                 self.read();
-                                let mut st = "0b".to_string();
+                                // `read_number` commence au caractère du préfixe
+                                let mut st = "0".to_string();
                                 st.push_str(&self.read_number());
                                 token!(Binary(st), begin => self.position)
                             },
                             'o' => { // octal
                                 self.read();
-                                let mut st = "0b".to_string();
+                                // `read_number` commence au caractère du préfixe
+                                let mut st = "0".to_string();
                                 st.push_str(&self.read_number());
                                 token!(Octal(st), begin => self.position)
                             },
                             'x' => { // hexadécimal
                                 self.read();
-                                let mut st = "0b".to_string();
+                                // `read_number` commence au caractère du préfixe
+                                let mut st = "0".to_string();
                                 st.push_str(&self.read_number());
                                 token!(Hexadecimal(st), begin => self.position)
                             },
-                            _ => token!(Decimal(self.read_number()), begin => self.position),
+                            _ => token!(self.read_decimal(), begin => self.position),
                         },
-                        _ => token!(Decimal(self.read_number()), begin => self.position),
+                        _ => token!(self.read_decimal(), begin => self.position),
                     }
                 },
                 _ => token!(Illegal(ch.to_string()), self.position),
@@ -198,6 +276,27 @@ impl<'a> Lexer<'a> {
         self.position
     }
 
+    /// Active ou désactive l'émission des espaces-blancs en tant que lexèmes
+    /// Avec les commentaires, ceux-ci forment les trivia: tout ce qui se trouve entre
+    /// deux lexèmes significatifs. Le texte original peut alors être reconstruit
+    /// à partir des lexèmes, à l'exception de l'en-tête du fichier.
+    pub fn emit_trivia(&mut self, enabled: bool) {
+        self.trivia = enabled;
+    }
+
+    /// Choisit les mot-clés reconnus par le lexer
+    /// La directive `#![keywords(fr)]` au début du fichier active aussi `KeywordSet::Bilingual`
+    pub fn set_keyword_set(&mut self, keywords: KeywordSet) {
+        self.keywords = keywords;
+        self.configured_keywords = keywords;
+    }
+
+    /// Le shebang et les directives du début du fichier
+    /// Ceux-ci sont lus lors du premier appel à `read_token`
+    pub fn file_attributes(&self) -> &FileAttributes {
+        &self.attributes
+    }
+
     /// Renvoie si le caractère actuel est celui passé en argument
     #[inline]
     fn current_char_is(&self, other: char) -> bool {
@@ -220,8 +319,13 @@ impl<'a> Lexer<'a> {
     /// Permet de voir le prochain caractère sans consommer le caractère
     /// renvoie `None` si la fin de la séquence est atteinte
     #[inline]
-    fn peek(&mut self) -> Option<&char> {
-        self.input.peek()
+    fn peek(&self) -> Option<char> {
+        self.input.clone().next()
+    }
+
+    /// Le caractère `n` positions après le caractère courant, `peek_nth(0)` étant `peek()`
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.input.clone().nth(n)
     }
 
     /// Renvoie le prochain caractère, le consommant de l'itérateur
@@ -249,18 +353,29 @@ impl<'a> Lexer<'a> {
         self.current_char
     }
 
+    /// Lit l'opérateur le plus long débutant par le caractère courant
+    fn read_operator(&mut self, first: char) -> LResult<Token> {
+        let begin = self.position;
+        match operators::longest_match(first, self.input.clone()) {
+            Some((1, token_type)) => token!(token_type.clone(), begin),
+            Some((length, token_type)) => {
+                for _ in 1..length {
+                    self.read();
+                }
+                token!(token_type.clone(), begin => self.position)
+            },
+            None => token!(TokenType::Illegal(first.to_string()), begin),
+        }
+    }
+
     /// Permet de lire un identifiant contenant optionnellement un '?'
     /// (question mark) à la fin
     fn read_identifier(&mut self) -> String {
         let mut st: String = self.current_char.unwrap().to_string();
-        {
-            let iter = self.input
-                .peeking_take_while(|ch| ch.is_alphabetic() || *ch == '_');
-            st.extend(iter);
-        }
+        self.read_class(&mut st, IDENTIFIER, |ch| ch.is_alphabetic() || *ch == '_');
 
         // permet d'avoir un point d'interrogation à la fin d'un identifiant
-        if let Some(&ch) = self.peek() {
+        if let Some(ch) = self.peek() {
             if ch == '?' {
                 self.read();
                 st.push(ch);
@@ -269,36 +384,135 @@ impl<'a> Lexer<'a> {
         st
     }
 
-    /// Lit un commentaire
-    fn read_comment(&mut self) -> String {
-        if self.current_char_is('/') { // lit un commentaire de ligne
-            self.input.peeking_take_while(is_newline).collect()
-        }
-        else { // lit un commentaire de bloc
-            let mut last_ch: char = 0 as u8 as char;
-            self.input
-                .peeking_take_while(|ch| {
-                    if last_ch != '*' && *ch != '/' {
-                        last_ch = ch.clone();
-                        true
-                    }
-                    else {
-                        false
-                    }
-                })
-                .collect()
+    /// Lit un commentaire de bloc, incluant les délimiteurs `/*` et `*/`
+    fn read_comment(&mut self) -> LResult<String> {
+        let mut st = "/*".to_string();
+        self.read(); // '*'
+
+        let mut previous_ch = '\0';
+        while let Some(current_ch) = self.read() {
+            st.push(current_ch);
+            if previous_ch == '*' && current_ch == '/' {
+                return Ok(st);
+            }
+            previous_ch = current_ch;
         }
+
+        Err(Error::UnexpectedEOF(self.position))
+    }
+
+    /// Lit un commentaire `// ...` jusqu'à la fin de la ligne
+    /// La fin de ligne n'en fait pas partie, elle est lue comme un espace-blanc
+    fn read_line_comment(&mut self) -> String {
+        let mut st = self.current_char.unwrap().to_string();
+        self.read_while(&mut st, |ch| !is_newline(ch));
+        st
+    }
+
+    /// Lit une suite d'espaces-blancs
+    fn read_whitespace(&mut self) -> String {
+        let mut st = self.current_char.unwrap().to_string();
+        self.read_while(&mut st, |ch| ch.is_whitespace());
+        st
     }
 
     /// Permet de lire un nombre
     #[inline]
     fn read_number(&mut self) -> String {
         let mut st = self.current_char.unwrap().to_string();
-        let iter = self.input.peeking_take_while(|ch| is_hex(ch) || *ch == '_');
-        st.extend(iter);
+        self.read_class(&mut st, HEX, |ch| is_hex(ch) || *ch == '_');
         st
     }
 
+    /// Lit un nombre décimal, entier ou à virgule flottante (`3.14`, `1e-9`, `2.5E+3`)
+    /// Un '.' n'est une virgule que s'il est suivi d'un chiffre: `0..10` et `1.abs()`
+    /// restent des entiers suivis d'un opérateur.
+    fn read_decimal(&mut self) -> Number {
+        let mut st = self.current_char.unwrap().to_string();
+        let mut float = false;
+        self.read_class(&mut st, DECIMAL, |ch| is_digit(ch) || *ch == '_');
+
+        if self.peek() == Some('.') && self.peek_nth(1).map_or(false, |ch| is_digit(&ch)) {
+            float = true;
+            st.push(self.read().unwrap());
+            self.read_class(&mut st, DECIMAL, |ch| is_digit(ch) || *ch == '_');
+        }
+
+        if let Some(exponent) = self.peek() {
+            // le signe de l'exposant est optionnel, mais un chiffre doit suivre
+            let digit_at = match self.peek_nth(1) {
+                Some('+') | Some('-') => 2,
+                _ => 1,
+            };
+            let has_digits = self.peek_nth(digit_at).map_or(false, |ch| is_digit(&ch));
+            if (exponent == 'e' || exponent == 'E') && has_digits {
+                float = true;
+                for _ in 0..digit_at {
+                    st.push(self.read().unwrap());
+                }
+                self.read_class(&mut st, DECIMAL, |ch| is_digit(ch) || *ch == '_');
+            }
+        }
+
+        if float { Number::Float(st) } else { Number::Decimal(st) }
+    }
+
+    /// Consomme les prochains caractères tant qu'ils respectent le prédicat
+    /// et les ajoute à `st`, en mettant à jour la position du lexer
+    fn read_while<F>(&mut self, st: &mut String, predicate: F)
+        where
+            F: Fn(&char) -> bool,
+    {
+        while let Some(ch) = self.peek() {
+            if !predicate(&ch) {
+                break;
+            }
+            self.read();
+            st.push(ch);
+        }
+    }
+
+    /// `read_while` pour une classe de `ASCII_CLASSES`, `predicate` devant accepter les
+    /// mêmes caractères ASCII: les suites ASCII sont lues par `read_ascii`, le prédicat
+    /// n'est appelé que pour les autres caractères
+    fn read_class<F>(&mut self, st: &mut String, class: u8, predicate: F)
+        where
+            F: Fn(&char) -> bool,
+    {
+        loop {
+            self.read_ascii(st, class);
+            match self.peek() {
+                Some(ch) if predicate(&ch) => {
+                    self.read();
+                    st.push(ch);
+                },
+                _ => break,
+            }
+        }
+    }
+
+    /// Consomme les prochains octets ASCII de la classe `class` et les ajoute à `st`
+    /// Les octets sont lus directement, sans décoder les caractères un à un.
+    /// Aucune classe ne contenant de fin de ligne, seule la colonne avance.
+    /// Renvoie le nombre de caractères lus.
+    fn read_ascii(&mut self, st: &mut String, class: u8) -> usize {
+        // `read` change de ligne au caractère suivant une fin de ligne
+        if self.current_char.map_or(true, |ch| is_newline(&ch)) {
+            return 0;
+        }
+        let rest = self.input.as_str();
+        let length = rest.bytes()
+            .take_while(|&byte| byte < 0x80 && ASCII_CLASSES[byte as usize] & class != 0)
+            .count();
+        if length > 0 {
+            st.push_str(&rest[..length]);
+            self.current_char = Some(char::from(rest.as_bytes()[length - 1]));
+            self.position.column += length;
+            self.input = rest[length..].chars();
+        }
+        length
+    }
+
     /// Lit une chaîne de caractères jusqu'à un '"' non-échappé
     fn read_string(&mut self) -> LResult<String> {
         // nous voulons itérer sur la séquence jusqu'à ce que nous trouvions
@@ -310,8 +524,17 @@ impl<'a> Lexer<'a> {
 
         // nous devons connaître le caractère précédent pour savoir si échappé
         let mut previous_ch = '\0';
-        // pour avoir la bonne position avec les newline
-        while let Some(current_ch) = self.read() {
+        loop {
+            // les caractères ordinaires ne terminent pas la chaîne et n'échappent rien
+            if self.read_ascii(&mut st, STRING) > 0 {
+                previous_ch = '\0';
+            }
+            // pour avoir la bonne position avec les newline
+            let current_ch = match self.read() {
+                Some(ch) => ch,
+                None => break,
+            };
+
             if is_newline(&current_ch) {
                 return Err(Error::UnterminatedString(self.position))
             }
@@ -339,15 +562,121 @@ impl<'a> Lexer<'a> {
         Err(Error::UnexpectedEOF(self.position))
     }
 
+    /// Saute l'entrée fautive après une erreur
+    /// Une erreur dans une chaîne de caractères est suivie du reste de la chaîne, qui ne doit
+    /// pas être relu: son guillemet fermant débuterait une nouvelle chaîne non-terminée.
+    fn recover(&mut self, error: &Error) {
+        if let Error::InvalidString(..) = *error {
+            let mut previous_ch = '\0';
+            while let Some(ch) = self.current_char {
+                if is_newline(&ch) || (ch == '"' && previous_ch != '\\') {
+                    break;
+                }
+                // un échappe échappé n'échappe pas le caractère suivant
+                previous_ch = if previous_ch == '\\' && ch == '\\' { '\0' } else { ch };
+                self.read();
+            }
+        }
+        self.read();
+    }
+
+    /// Lit un shebang optionnel sur la première ligne suivi de directives `#![...]`
+    /// `#![` n'est jamais un shebang, tout comme dans Rust
+    fn read_file_header(&mut self) -> LResult<()> {
+        if self.current_char_is('#') && self.peek() == Some('!') {
+            let mut lookahead = self.input.clone();
+            lookahead.next(); // '!'
+            if lookahead.next() != Some('[') {
+                let mut shebang = String::new();
+                while let Some(ch) = self.current_char {
+                    if is_newline(&ch) {
+                        break;
+                    }
+                    shebang.push(ch);
+                    self.read();
+                }
+                self.attributes.shebang = Some(shebang);
+            }
+        }
+
+        loop {
+            // les espaces-blancs ne font partie de l'en-tête que s'ils précèdent une directive,
+            // sinon ils sont émis comme trivia
+            if !self.pragma_follows() {
+                return Ok(());
+            }
+            self.skip_whitespace();
+
+            let begin = self.position;
+            self.read(); // '!'
+            let next = self.read();
+            self.expect_char(next, '[')?;
+
+            let mut content = String::new();
+            loop {
+                match self.read() {
+                    Some(']') => break,
+                    Some(ch) if !is_newline(&ch) => content.push(ch),
+                    _ => return Err(Error::InvalidPragma(content, begin)),
+                }
+            }
+
+            let location = Span::new(begin, self.position);
+            match Pragma::parse(&content, location) {
+                Some(pragma) => {
+                    if pragma.name == "keywords" && pragma.arguments == ["fr"] {
+                        self.keywords = KeywordSet::Bilingual;
+                    }
+                    trace_event!("directive de fichier: {:?}", pragma);
+                    self.attributes.pragmas.push(pragma);
+                },
+                None => return Err(Error::InvalidPragma(content, begin)),
+            }
+            self.read(); // passe le ']'
+        }
+    }
+
+    /// Si `#!` suit les espaces-blancs à partir du caractère actuel, sans rien consommer
+    fn pragma_follows(&self) -> bool {
+        let mut chars = self.current_char.into_iter()
+            .chain(self.input.clone())
+            .skip_while(|ch| ch.is_whitespace());
+        chars.next() == Some('#') && chars.next() == Some('!')
+    }
+
     /// Saute les espaces-blancs, incluant le retour à la ligne
     #[inline]
     fn skip_whitespace(&mut self) {
-        self.input
-            .by_ref()
-            .skip_while(|ch| ch.is_whitespace());
+        while let Some(ch) = self.current_char {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.read();
+        }
     }
 }
 
+/// Les lettres ASCII et `_`, qui forment le corps d'un identifiant
+const IDENTIFIER: u8 = 1;
+/// Les chiffres décimaux et `_`
+const DECIMAL: u8 = 2;
+/// Les chiffres hexadécimaux et `_`
+const HEX: u8 = 4;
+/// Les caractères imprimables d'une chaîne, sauf `"` et `\`
+const STRING: u8 = 8;
+
+/// Les classes de chaque caractère ASCII, combinées par un ou binaire
+static ASCII_CLASSES: [u8; 128] = [
+     0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,
+     8,  8,  0,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,
+    14, 14, 14, 14, 14, 14, 14, 14, 14, 14,  8,  8,  8,  8,  8,  8,
+     8, 13, 13, 13, 13, 13, 13,  9,  9,  9,  9,  9,  9,  9,  9,  9,
+     9,  9,  9,  9,  9,  9,  9,  9,  9,  9,  9,  8,  0,  8,  8, 15,
+     8, 13, 13, 13, 13, 13, 13,  9,  9,  9,  9,  9,  9,  9,  9,  9,
+     9,  9,  9,  9,  9,  9,  9,  9,  9,  9,  9,  8,  8,  8,  8,  0,
+];
+
 /// Renvoie si le caractère actuel est un chiffre décimal
 #[inline]
 fn is_digit(ch: &char) -> bool {
@@ -368,14 +697,85 @@ fn is_octal(ch: &char) -> bool {
 
 /// Renvoie `true` si le caractère est une fin de ligne
 /// Supporte les fins de ligne de plusieurs OS
+/// Le caractère NUL n'est pas une fin de ligne
 #[inline]
-fn is_newline(ch: &char) -> bool {
+pub(crate) fn is_newline(ch: &char) -> bool {
     match *ch {
-        '\u{000A}'...'\u{000D}' | '\u{0085}' | '\u{2028}' | '\u{2029}' | '\0' => true,
+        '\u{000A}'...'\u{000D}' | '\u{0085}' | '\u{2028}' | '\u{2029}' => true,
         _ => false,
     }
 }
 
+/// Décode les séquences d'échappement d'une chaîne lue par `read_string`, sans ses guillemets
+/// Séquences reconnues: `\n`, `\t`, `\r`, `\\`, `\"`, `\0` et `\u{XXXX}` (1 à 6 chiffres).
+/// `begin` est la position du guillemet ouvrant: une chaîne ne contenant pas de fin de
+/// ligne, la colonne de chaque caractère s'en déduit.
+fn decode_escapes(raw: &str, begin: Position) -> LResult<String> {
+    let inner = &raw[1..raw.len() - 1];
+    let column = |i: usize| Position::new(begin.line, begin.column + 1 + i);
+    let mut decoded = String::with_capacity(inner.len());
+    let mut chars = inner.chars().enumerate().peekable();
+
+    while let Some((i, ch)) = chars.next() {
+        if ch != '\\' {
+            decoded.push(ch);
+            continue;
+        }
+        let (end, escaped) = match chars.next() {
+            Some((j, 'n')) => (j, Some('\n')),
+            Some((j, 't')) => (j, Some('\t')),
+            Some((j, 'r')) => (j, Some('\r')),
+            Some((j, '\\')) => (j, Some('\\')),
+            Some((j, '"')) => (j, Some('"')),
+            Some((j, '0')) => (j, Some('\0')),
+            Some((j, 'u')) => decode_unicode_escape(&mut chars, j),
+            Some((j, _)) => (j, None),
+            // `read_string` ne termine pas une chaîne sur un guillemet échappé
+            None => (i, None),
+        };
+        match escaped {
+            Some(ch) => decoded.push(ch),
+            None => {
+                let sequence = inner.chars().skip(i).take(end - i + 1).collect();
+                return Err(Error::InvalidEscape(sequence, Span::new(column(i), column(end))));
+            },
+        }
+    }
+
+    Ok(decoded)
+}
+
+/// Décode la suite `{XXXX}` d'un `\u`, `u_index` étant l'indice du `u`
+/// Renvoie l'indice du dernier caractère lu et le caractère, `None` si la séquence est invalide
+fn decode_unicode_escape(
+    chars: &mut Peekable<Enumerate<Chars>>,
+    u_index: usize,
+) -> (usize, Option<char>) {
+    let mut end = match chars.peek() {
+        Some(&(j, '{')) => j,
+        _ => return (u_index, None),
+    };
+    chars.next();
+
+    let mut hex = String::new();
+    while let Some(&(j, ch)) = chars.peek() {
+        if !is_hex(&ch) || hex.len() == 6 {
+            break;
+        }
+        hex.push(ch);
+        end = j;
+        chars.next();
+    }
+    match chars.peek() {
+        Some(&(j, '}')) if !hex.is_empty() => {
+            chars.next();
+            let ch = u32::from_str_radix(&hex, 16).ok().and_then(::std::char::from_u32);
+            (j, ch)
+        },
+        _ => (end, None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -475,6 +875,31 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn string_escapes() {
+        let literal = |source| match Lexer::new(source).read_token() {
+            Ok(token) => Ok(token.token_type().clone()),
+            Err(err) => Err(err),
+        };
+        let span = |begin, end| Span::new(Position::new(1, begin), Position::new(1, end));
+
+        assert_eq!(
+            Ok(TokenType::Literal("a\n\t\r\\\"\0é😀".to_string())),
+            literal(r#""a\n\t\r\\\"\0\u{e9}\u{1F600}""#)
+        );
+        assert_eq!(Ok(TokenType::Literal(String::new())), literal("\"\""));
+        assert_eq!(Err(Error::InvalidEscape(r"\q".to_string(), span(4, 5))), literal(r#""ab\q""#));
+        assert_eq!(
+            Err(Error::InvalidEscape(r"\u{D800}".to_string(), span(2, 9))),
+            literal(r#""\u{D800}""#)
+        );
+        assert_eq!(
+            Err(Error::InvalidEscape(r"\u{12".to_string(), span(2, 6))),
+            literal(r#""\u{12""#)
+        );
+        assert_eq!(Err(Error::InvalidEscape(r"\u".to_string(), span(2, 3))), literal(r#""\u41""#));
+    }
+
     #[test]
     fn read_string_with_newline_should_error() {
         test_lexer!(read_string, [
@@ -497,6 +922,317 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn file_header() {
+        use lexer::attributes::Pragma;
+
+        let mut lexer = Lexer::new(
+            "#!/usr/bin/env nbc\n#![allow(unused, naming_convention)]\n#![strict]\nlet"
+        );
+        let token = lexer.read_token().map(|tok| tok.token_type().clone());
+
+        assert_eq!(Ok(TokenType::Keyword(Keyword::Let)), token);
+        assert_eq!(&FileAttributes {
+            shebang: Some("#!/usr/bin/env nbc".to_string()),
+            pragmas: vec![
+                Pragma {
+                    name: "allow".to_string(),
+                    arguments: vec!["unused".to_string(), "naming_convention".to_string()],
+                    location: Span::new(Position::new(2, 1), Position::new(2, 36)),
+                },
+                Pragma {
+                    name: "strict".to_string(),
+                    arguments: vec![],
+                    location: Span::new(Position::new(3, 1), Position::new(3, 10)),
+                },
+            ],
+        }, lexer.file_attributes());
+    }
+
+    #[test]
+    fn file_header_invalid_pragma() {
+        test_lexer!(read_token, [
+            "#![allow(unused]\nlet"
+                => Err(Error::InvalidPragma("allow(unused".to_string(), Position::new(1, 1))),
+            "#![allow\n]"
+                => Err(Error::InvalidPragma("allow".to_string(), Position::new(1, 1))),
+        ]);
+    }
+
+    #[test]
+    fn read_comment() {
+        test_lexer!(read_token, [
+            "/* un\ncommentaire **/ let" => token!(
+                TokenType::Comment("/* un\ncommentaire **/".to_string()),
+                Position::new(1, 1) => Position::new(2, 15)
+            ),
+            "/* non terminé *" => Err(Error::UnexpectedEOF(Position::new(1, 16))),
+            "// une ligne\nlet" => token!(
+                TokenType::Comment("// une ligne".to_string()),
+                Position::new(1, 1) => Position::new(1, 12)
+            ),
+            "//" => token!(
+                TokenType::Comment("//".to_string()),
+                Position::new(1, 1) => Position::new(1, 2)
+            ),
+        ]);
+
+        let (tokens, errors) = Lexer::new("a // b /* c\r\n/ d").tokenize_all();
+        let token_types: Vec<_> = tokens.iter().map(|tok| tok.token_type().clone()).collect();
+        assert!(errors.is_empty());
+        assert_eq!(vec![
+            TokenType::Identifier("a".to_string()),
+            TokenType::Comment("// b /* c".to_string()),
+            TokenType::Division,
+            TokenType::Identifier("d".to_string()),
+        ], token_types);
+        assert_eq!(Position::new(2, 1), tokens[2].location().span().begin());
+    }
+
+    #[test]
+    fn trivia() {
+        use token::{Keyword::*, TokenType::*};
+
+        let mut lexer = Lexer::new("let  ab /* c */\n;");
+        lexer.emit_trivia(true);
+
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.read_token().unwrap();
+            if *token.token_type() == EOF {
+                break;
+            }
+            tokens.push((token.token_type().clone(), *token.location()));
+        }
+
+        let span = |line, begin, end| -> PositionOrSpan {
+            Span::new(Position::new(line, begin), Position::new(line, end)).into()
+        };
+        assert_eq!(vec![
+            (Keyword(Let), span(1, 1, 3)),
+            (Whitespace("  ".to_string()), span(1, 4, 5)),
+            (Identifier("ab".to_string()), span(1, 6, 7)),
+            (Whitespace(" ".to_string()), span(1, 8, 8)),
+            (Comment("/* c */".to_string()), span(1, 9, 15)),
+            (Whitespace("\n".to_string()), span(1, 16, 16)),
+            (Semicolon, Position::new(2, 1).into()),
+        ], tokens);
+    }
+
+    #[test]
+    fn leading_whitespace_trivia() {
+        use token::{Keyword::*, TokenType::*};
+
+        let token_types = |source| {
+            let mut lexer = Lexer::new(source);
+            lexer.emit_trivia(true);
+            let (tokens, errors) = lexer.tokenize_all();
+            assert!(errors.is_empty());
+            tokens.into_iter().map(|tok| tok.token_type().clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec![Whitespace("\n  ".to_string()), Keyword(Let)], token_types("\n  let"));
+        // les espaces-blancs précédant une directive font partie de l'en-tête
+        assert_eq!(
+            vec![Whitespace("\n".to_string()), Keyword(Let)],
+            token_types("\n #![strict]\nlet")
+        );
+    }
+
+    #[test]
+    fn french_keywords() {
+        use token::{Keyword::*, TokenType::*};
+
+        let token_types = |mut lexer: Lexer| {
+            let mut types = Vec::new();
+            loop {
+                match lexer.read_token().unwrap().token_type().clone() {
+                    EOF => return types,
+                    token_type => types.push(token_type),
+                }
+            }
+        };
+
+        let mut lexer = Lexer::new("fonction si tantque retourner");
+        lexer.set_keyword_set(KeywordSet::Bilingual);
+        assert_eq!(
+            vec![Keyword(Fun), Keyword(If), Keyword(While), Keyword(Return)],
+            token_types(lexer)
+        );
+
+        assert_eq!(
+            vec![Keyword(Let), Keyword(Let)],
+            token_types(Lexer::new("#![keywords(fr)]\nsoit let"))
+        );
+
+        assert_eq!(
+            vec![Identifier("si".to_string())],
+            token_types(Lexer::new("si"))
+        );
+    }
+
+    #[test]
+    fn tokenize_with() {
+        let mut count = 0;
+        assert_eq!(Ok(()), Lexer::tokenize_with("let x = 5;", |_| count += 1));
+        assert_eq!(5, count);
+
+        let mut batches = Vec::new();
+        let result = Lexer::tokenize_batches("a b c d e \"f", 2, |batch| {
+            batches.push(batch.len())
+        });
+        assert_eq!(Err(Error::UnexpectedEOF(Position::new(1, 12))), result);
+        assert_eq!(vec![2, 2, 1], batches);
+    }
+
+    #[test]
+    fn tokenize_all() {
+        use token::TokenType::*;
+
+        let mut lexer = Lexer::new("let a = \"x\n; b \0 c \"fin");
+        let (tokens, errors) = lexer.tokenize_all();
+        let token_types: Vec<_> = tokens.iter().map(|tok| tok.token_type().clone()).collect();
+
+        assert_eq!(vec![
+            Keyword(::token::Keyword::Let), Identifier("a".to_string()), Eq,
+            Illegal("\n".to_string()), Semicolon, Identifier("b".to_string()),
+            Illegal("\0".to_string()), Identifier("c".to_string()), Illegal(String::new()),
+        ], token_types);
+        assert_eq!(vec![
+            Error::UnterminatedString(Position::new(1, 11)),
+            Error::NulCharacter(Position::new(2, 5)),
+            Error::UnexpectedEOF(Position::new(2, 12)),
+        ], errors);
+    }
+
+    #[test]
+    fn tokenize_all_skips_invalid_strings() {
+        use token::TokenType::*;
+
+        // le guillemet fermant d'une chaîne invalide ne débute pas une nouvelle chaîne
+        let (tokens, errors) = Lexer::new("a = \"x\ty \\\" z\"; b\nc = \"\t\nd").tokenize_all();
+        let token_types: Vec<_> = tokens.iter().map(|tok| tok.token_type().clone()).collect();
+
+        assert_eq!(vec![
+            Identifier("a".to_string()), Eq, Illegal("\t".to_string()), Semicolon,
+            Identifier("b".to_string()), Identifier("c".to_string()), Eq,
+            Illegal("\t".to_string()), Identifier("d".to_string()),
+        ], token_types);
+        assert_eq!(vec![
+            Error::InvalidString("\"x".to_string(), Position::new(1, 7)),
+            Error::InvalidString("\"".to_string(), Position::new(2, 6)),
+        ], errors);
+    }
+
+    #[test]
+    fn ascii_runs() {
+        use token::TokenType::*;
+        use token::Number::*;
+
+        // les suites ASCII sont lues octet par octet, les autres caractères un à un
+        let (tokens, errors) = Lexer::new("ab_é_cd 1_0 0xF_f \"a\\\"é b\"\nx").tokenize_all();
+        let tokens: Vec<_> = tokens.iter()
+            .map(|tok| (tok.token_type().clone(), tok.location().span().begin()))
+            .collect();
+
+        assert_eq!(Vec::<Error>::new(), errors);
+        assert_eq!(vec![
+            (Identifier("ab_é_cd".to_string()), Position::new(1, 1)),
+            (Number(Decimal("1_0".to_string())), Position::new(1, 9)),
+            (Number(Hexadecimal("0xF_f".to_string())), Position::new(1, 13)),
+            (Literal("a\"é b".to_string()), Position::new(1, 19)),
+            (Identifier("x".to_string()), Position::new(2, 1)),
+        ], tokens);
+    }
+
+    #[test]
+    fn reset() {
+        let mut lexer = Lexer::new("#![keywords(fr)]\nsoit a = \0;");
+        lexer.emit_trivia(true);
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        lexer.tokenize_all_into(&mut tokens, &mut errors);
+        assert_eq!(1, errors.len());
+        let capacity = tokens.capacity();
+
+        lexer.reset("soit");
+        lexer.tokenize_all_into(&mut tokens, &mut errors);
+        let token_types: Vec<_> = tokens.iter().map(|tok| tok.token_type().clone()).collect();
+        // la directive n'est pas conservée, mais l'émission des trivia l'est
+        assert_eq!(vec![TokenType::Identifier("soit".to_string())], token_types);
+        assert_eq!(Position::new(1, 1), tokens[0].location().span().begin());
+        assert!(errors.is_empty());
+        assert_eq!(capacity, tokens.capacity());
+
+        lexer.reset("a b");
+        lexer.tokenize_all_into(&mut tokens, &mut errors);
+        assert_eq!(TokenType::Whitespace(" ".to_string()), *tokens[1].token_type());
+    }
+
+    #[test]
+    fn float_literals() {
+        use token::{Number::*, TokenType::*};
+
+        let token_types = |source| -> Vec<TokenType> {
+            let (tokens, errors) = Lexer::new(source).tokenize_all();
+            assert!(errors.is_empty(), "{:?}", errors);
+            tokens.iter().map(|tok| tok.token_type().clone()).collect()
+        };
+        let float = |st: &str| Number(Float(st.to_string()));
+        let decimal = |st: &str| Number(Decimal(st.to_string()));
+
+        assert_eq!(vec![float("3.14"), float("1e-9"), float("2.5E+3"), float("1_000.000_1")],
+                   token_types("3.14 1e-9 2.5E+3 1_000.000_1"));
+        assert_eq!(vec![decimal("0"), DotDot, decimal("10")], token_types("0..10"));
+        assert_eq!(vec![decimal("1"), Dot, Identifier("abs".to_string())], token_types("1.abs"));
+        assert_eq!(vec![decimal("2"), Identifier("e".to_string())], token_types("2e"));
+        assert_eq!(vec![decimal("0"), float("0.5")], token_types("0 0.5"));
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        test_lexer!(read_token, [
+            "\u{FEFF}let" => token!(Keyword::Let, Position::new(1, 1) => Position::new(1, 3)),
+        ]);
+    }
+
+    #[test]
+    fn nul_character() {
+        test_lexer!(read_token, 3, [
+            "ab\n\0" => Err(Error::NulCharacter(Position::new(2, 1))),
+        ]);
+        test_lexer!(read_string, [
+            "\"a\0\"" => Err(Error::InvalidString("\"a".to_string(), Position::new(1, 3))),
+        ]);
+    }
+
+    #[test]
+    fn operators_longest_match() {
+        use token::TokenType::*;
+
+        test_lexer!([
+            "a += b -= c *= d /= e %= f ^= g" => [
+                Identifier("a".to_string()), PlusEq, Identifier("b".to_string()), MinusEq,
+                Identifier("c".to_string()), MultiplicationEq, Identifier("d".to_string()),
+                DivisionEq, Identifier("e".to_string()), ModuloEq, Identifier("f".to_string()),
+                PowerEq, Identifier("g".to_string())
+            ],
+            "<<= << <= < >>= >> >= >" => [ShlEq, Shl, LtEq, Lt, ShrEq, Shr, GtEq, Gt],
+            "a.b ..x ... ?" => [
+                Identifier("a".to_string()), Dot, Identifier("b".to_string()), DotDot,
+                Identifier("x".to_string()), DotDot, Dot, Question
+            ],
+            "a>>=b>>==c" => [
+                Identifier("a".to_string()), ShrEq, Identifier("b".to_string()), ShrEq, Eq,
+                Identifier("c".to_string())
+            ],
+            "vide? ? b : c * d" => [
+                Identifier("vide?".to_string()), Question, Identifier("b".to_string()), Colon,
+                Identifier("c".to_string()), Multiplication, Identifier("d".to_string())
+            ],
+        ]);
+    }
+
     #[test]
     fn tokenize() {
         use token::{
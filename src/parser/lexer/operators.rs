@@ -0,0 +1,105 @@
+//! Reconnaissance des opérateurs par plus longue correspondance
+//!
+//! Plutôt que d'imbriquer des `match self.peek()` pour chaque préfixe commun
+//! (`>`, `>=`, `>>`, `>>=`), les opérateurs sont décrits dans une table et
+//! l'opérateur reconnu est toujours le plus long qui correspond à l'entrée.
+//! Ainsi `>>=` n'est jamais découpé en `>` suivi de `>=`.
+
+use token::TokenType;
+use token::TokenType::*;
+
+/// Table des opérateurs, incluant la ponctuation pouvant former un préfixe commun
+pub(crate) static OPERATORS: &[(&str, TokenType)] = &[
+    ("=", Eq),
+    ("==", EqEq),
+    ("!", Not),
+    ("!=", NotEq),
+    ("~", Tilde),
+    ("+", Plus),
+    ("+=", PlusEq),
+    ("-", Minus),
+    ("-=", MinusEq),
+    ("*", Multiplication),
+    ("*=", MultiplicationEq),
+    ("/", Division),
+    ("/=", DivisionEq),
+    ("%", Modulo),
+    ("%=", ModuloEq),
+    ("^", Power),
+    ("^=", PowerEq),
+    ("^^", Xor),
+    ("<", Lt),
+    ("<=", LtEq),
+    ("<<", Shl),
+    ("<<=", ShlEq),
+    (">", Gt),
+    (">=", GtEq),
+    (">>", Shr),
+    (">>=", ShrEq),
+    ("|", Or),
+    ("||", OrOr),
+    ("&", And),
+    ("&&", AndAnd),
+    (".", Dot),
+    ("..", DotDot),
+    ("?", Question),
+];
+
+/// Longueur, en caractères, du plus long opérateur de la table
+const MAX_LENGTH: usize = 3;
+
+/// Si un opérateur débute par ce caractère
+pub(crate) fn is_operator_start(ch: char) -> bool {
+    OPERATORS.iter().any(|&(op, _)| op.starts_with(ch))
+}
+
+/// Renvoie l'opérateur le plus long correspondant à `first` suivi des caractères de `rest`,
+/// ainsi que son nombre de caractères
+/// `rest` n'est pas consommé au-delà de la longueur du plus long opérateur
+pub(crate) fn longest_match<I>(first: char, rest: I) -> Option<(usize, &'static TokenType)>
+where
+    I: Iterator<Item = char>,
+{
+    let mut lookahead = first.to_string();
+    lookahead.extend(rest.take(MAX_LENGTH - 1));
+
+    OPERATORS
+        .iter()
+        .filter(|&&(op, _)| lookahead.starts_with(op))
+        .max_by_key(|&&(op, _)| op.len())
+        .map(|&(op, ref token_type)| (op.chars().count(), token_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(input: &str) -> Option<(usize, TokenType)> {
+        let mut chars = input.chars();
+        let first = chars.next().unwrap();
+        longest_match(first, chars).map(|(len, token_type)| (len, token_type.clone()))
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(Some((3, ShrEq)), matched(">>="));
+        assert_eq!(Some((2, Shr)), matched(">>"));
+        assert_eq!(Some((2, GtEq)), matched(">=>"));
+        assert_eq!(Some((1, Gt)), matched("> >"));
+        assert_eq!(Some((3, ShlEq)), matched("<<=="));
+        assert_eq!(Some((2, DotDot)), matched("..."));
+        assert_eq!(Some((1, Tilde)), matched("~="));
+        assert_eq!(Some((2, Xor)), matched("^^="));
+        assert_eq!(Some((2, PowerEq)), matched("^=^"));
+        assert_eq!(None, matched("@"));
+    }
+
+    #[test]
+    fn every_operator_matches_itself() {
+        for &(op, ref token_type) in OPERATORS {
+            assert!(op.chars().count() <= MAX_LENGTH);
+            assert!(is_operator_start(op.chars().next().unwrap()));
+            assert_eq!(Some((op.chars().count(), token_type.clone())), matched(op));
+        }
+    }
+}
@@ -16,11 +16,66 @@ extern crate itertools;
 extern crate phf;
 extern crate failure;
 
+#[cfg(feature = "logging")]
+#[macro_use]
+extern crate log;
+
 
 
+#[macro_use]
+mod logging;
 #[macro_use]
 pub mod token;
 
+pub mod analysis;
 pub mod ast;
+pub mod desugar;
+pub mod diagnostic;
+pub mod edits;
 pub mod lexer;
+pub mod lint;
+pub mod locale;
+pub mod mem;
 pub mod parser;
+pub mod stats;
+#[macro_use]
+pub mod test_util;
+pub mod types;
+pub mod validate;
+pub mod version;
+pub mod visit;
+
+use diagnostic::Diagnostic;
+use lexer::Lexer;
+use locale::Locale;
+use token::Token;
+
+/// Lit tous les lexèmes de `source` sans s'arrêter aux erreurs
+/// Raccourci pour `Lexer::new(source).tokenize_all()`, voir celui-ci.
+pub fn tokenize(source: &str) -> (Vec<Token>, Vec<lexer::error::Error>) {
+    Lexer::new(source).tokenize_all()
+}
+
+/// Vérifie `source` et renvoie les diagnostics à afficher, dans la langue demandée
+/// Pour l'instant, seules les erreurs du lexer sont rapportées.
+pub fn check(source: &str, locale: Locale) -> Vec<Diagnostic> {
+    let (_, errors) = tokenize(source);
+    errors.iter().map(|err| Diagnostic::from_lexer_error(err, locale)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convenience_functions() {
+        let (tokens, errors) = tokenize("let x = 1;");
+        assert_eq!(5, tokens.len());
+        assert!(errors.is_empty());
+
+        assert!(check("let x = 1;", Locale::French).is_empty());
+        let diagnostics = check("let s = \"abc", Locale::English);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Some("E0005"), diagnostics[0].code);
+    }
+}
@@ -0,0 +1,393 @@
+//! Linter vérifiant des règles de style et de qualité sur l'AST.
+//! Chaque règle est un `Visitor` accumulant ses messages lors du parcours.
+//! Le niveau de chaque règle (`Allow`, `Warn`, `Deny`) est configurable pour tout le
+//! fichier avec une directive `#![deny(regle)]`, et une règle peut être désactivée pour
+//! l'énoncé suivant un commentaire de la forme `/* nb-lint: allow(regle) */`.
+use ast::{FunctionDeclaration, Statement};
+use edits::TextEdit;
+use lexer::attributes::FileAttributes;
+use locale::Locale;
+use logging::Phase;
+use token::{Keyword, Token, TokenType};
+use visit::{self, Visitor};
+
+use std::collections::HashMap;
+use std::fmt;
+
+pub use self::naming::*;
+pub use self::options::LintOptions;
+pub use self::rules::*;
+
+pub mod naming;
+pub mod options;
+pub mod rules;
+
+/// Le niveau d'une règle
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Level {
+    /// La règle n'est pas vérifiée
+    Allow,
+    /// Une violation produit un avertissement
+    Warn,
+    /// Une violation produit une erreur
+    Deny,
+}
+
+impl Level {
+    /// Le nom du niveau dans la langue demandée
+    pub fn label(&self, locale: Locale) -> &'static str {
+        use self::Level::*;
+        match (*self, locale) {
+            (Allow, Locale::French) => "permis",
+            (Allow, Locale::English) => "allowed",
+            (Warn, Locale::French) => "avertissement",
+            (Warn, Locale::English) => "warning",
+            (Deny, Locale::French) => "erreur",
+            (Deny, Locale::English) => "error",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label(Locale::French))
+    }
+}
+
+/// Une règle du linter
+pub trait Rule: Visitor {
+    /// Le nom de la règle, utilisé pour la configuration et la suppression
+    fn name(&self) -> &'static str;
+
+    /// Le niveau de la règle lorsqu'il n'est pas configuré
+    fn default_level(&self) -> Level {
+        Level::Warn
+    }
+
+    /// Les messages accumulés par la règle
+    fn messages(&mut self) -> &mut Vec<Message>;
+
+    /// Renvoie les messages accumulés depuis le dernier appel
+    fn take_messages(&mut self) -> Vec<Message> {
+        ::std::mem::replace(self.messages(), Vec::new())
+    }
+}
+
+/// L'identité d'un énoncé, valide tant que l'AST qui le contient n'est pas déplacé
+/// L'AST ne contenant pas de positions, les suppressions sont associées aux énoncés ainsi.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct StatementId(usize);
+
+impl StatementId {
+    pub fn of(stmt: &Statement) -> Self {
+        StatementId(stmt as *const Statement as usize)
+    }
+}
+
+/// Un message produit par une règle
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Message {
+    pub text: String,
+    /// Un renommage corrigeant la violation, s'il y en a un
+    pub suggestion: Option<Suggestion>,
+    /// L'énoncé fautif, `None` pour une violation concernant la fonction elle-même
+    /// Seul un message associé à un énoncé peut être supprimé par un commentaire.
+    pub statement: Option<StatementId>,
+}
+
+impl Message {
+    /// Associe le message à l'énoncé fautif
+    pub fn at(self, stmt: &Statement) -> Self {
+        Message { statement: Some(StatementId::of(stmt)), ..self }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message { text, suggestion: None, statement: None }
+    }
+}
+
+/// Un renommage suggéré par une règle
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suggestion {
+    /// Le nom actuel
+    pub original: String,
+    /// Le nom à utiliser
+    pub replacement: String,
+}
+
+impl Suggestion {
+    /// Produit la correction de la première déclaration du nom `original`
+    /// L'AST ne contenant pas de positions, les lexèmes du programme sont utilisés: une
+    /// déclaration est un identifiant suivant `let`, `const` ou `fun`, ou un paramètre.
+    /// Sans résolution des noms, les utilisations ne sont pas renommées et la correction
+    /// seule laisse le programme invalide si le nom est utilisé.
+    pub fn fix(&self, tokens: &[Token]) -> Option<TextEdit> {
+        let tokens: Vec<_> = tokens
+            .iter()
+            .filter(|token| match *token.token_type() {
+                TokenType::Whitespace(_) | TokenType::Comment(_) => false,
+                _ => true,
+            })
+            .collect();
+        (0..tokens.len())
+            .find(|&idx| match *tokens[idx].token_type() {
+                TokenType::Identifier(ref name) => {
+                    *name == self.original && is_declaration(&tokens, idx)
+                },
+                _ => false,
+            })
+            .map(|idx| TextEdit::new(tokens[idx].location().span(), self.replacement.clone()))
+    }
+}
+
+/// Si l'identifiant à l'indice `idx` est déclaré à cet endroit
+fn is_declaration(tokens: &[&Token], idx: usize) -> bool {
+    let previous = if idx > 0 { Some(tokens[idx - 1].token_type()) } else { None };
+    let next = tokens.get(idx + 1).map(|token| token.token_type());
+    match (previous, next) {
+        (Some(&TokenType::Keyword(Keyword::Let)), _)
+        | (Some(&TokenType::Keyword(Keyword::Const)), _)
+        | (Some(&TokenType::Keyword(Keyword::Fun)), _) => true,
+        // un paramètre: `(nom: type` ou `, nom: type`
+        (Some(&TokenType::Lparen), Some(&TokenType::Colon))
+        | (Some(&TokenType::Comma), Some(&TokenType::Colon)) => true,
+        _ => false,
+    }
+}
+
+/// Une violation d'une règle
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lint {
+    /// Le nom de la règle
+    pub rule: &'static str,
+    /// Le niveau de la règle au moment de la vérification
+    pub level: Level,
+    /// Le message décrivant la violation
+    pub message: String,
+    /// Un renommage corrigeant la violation, s'il y en a un
+    pub suggestion: Option<Suggestion>,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.level, self.rule, self.message)
+    }
+}
+
+/// Erreur renvoyée lorsqu'une règle inconnue est configurée
+#[derive(Debug, Eq, Fail, PartialEq)]
+#[fail(display = "Règle de lint inconnue: '{}'", 0)]
+pub struct UnknownRule(pub String);
+
+/// Le linter contient les règles et leur niveau respectif
+pub struct Linter {
+    rules: Vec<(Box<Rule>, Level)>,
+    /// Si les avertissements sont traités comme des erreurs
+    deny_warnings: bool,
+    /// Les règles désactivées par un commentaire pour un énoncé et ses enfants
+    allowed: Vec<(StatementId, Vec<String>)>,
+}
+
+impl Linter {
+    /// Construit un linter avec toutes les règles incluses
+    pub fn new() -> Self {
+        Linter::with_rules(vec![
+            box ShadowedVariable::default(),
+            box ConstantCondition::default(),
+            box EmptyBlock::default(),
+            box NamingConvention::default(),
+            box UnusedParameter::default(),
+            box ChainedComparison::default(),
+            box PredicateReturnType::default(),
+        ])
+    }
+
+    /// Construit un linter avec les règles passées en paramètre
+    pub fn with_rules(rules: Vec<Box<Rule>>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let level = rule.default_level();
+                (rule, level)
+            })
+            .collect();
+        Linter { rules, deny_warnings: false, allowed: Vec::new() }
+    }
+
+    /// Les noms des règles du linter
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|&(ref rule, _)| rule.name()).collect()
+    }
+
+    /// Renvoie le niveau de la règle, `None` si elle n'existe pas
+    pub fn level(&self, name: &str) -> Option<Level> {
+        self.rules
+            .iter()
+            .find(|&&(ref rule, _)| rule.name() == name)
+            .map(|&(_, level)| level)
+    }
+
+    /// Change le niveau d'une règle
+    pub fn set_level(&mut self, name: &str, level: Level) -> Result<(), UnknownRule> {
+        match self.rules.iter_mut().find(|&&mut (ref rule, _)| rule.name() == name) {
+            Some(&mut (_, ref mut current)) => {
+                *current = level;
+                Ok(())
+            },
+            None => Err(UnknownRule(name.to_owned())),
+        }
+    }
+
+    /// Lit les règles désactivées par un commentaire `nb-lint: allow(regle_a, regle_b)`
+    /// Le niveau des règles n'est pas modifié: la suppression s'applique seulement à
+    /// l'énoncé suivant le commentaire, avec `allow_in`.
+    /// Un commentaire ne contenant pas de suppression renvoie une liste vide.
+    pub fn suppression_from_comment(&self, comment: &str) -> Result<Vec<String>, UnknownRule> {
+        let names = parse_suppression(comment);
+        match names.iter().find(|name| self.level(name).is_none()) {
+            Some(name) => Err(UnknownRule(name.clone())),
+            None => Ok(names),
+        }
+    }
+
+    /// Désactive des règles pour un énoncé et ses enfants, lors du prochain appel à `check`
+    pub fn allow_in(&mut self, stmt: &Statement, rules: Vec<String>) {
+        self.allowed.push((StatementId::of(stmt), rules));
+    }
+
+    /// Applique les directives `#![allow(...)]`, `#![warn(...)]` et `#![deny(...)]`
+    /// du début du fichier. Les autres directives sont ignorées.
+    pub fn configure(&mut self, attributes: &FileAttributes) -> Result<(), UnknownRule> {
+        for pragma in &attributes.pragmas {
+            let level = match pragma.name.as_str() {
+                "allow" => Level::Allow,
+                "warn" => Level::Warn,
+                "deny" => Level::Deny,
+                _ => continue,
+            };
+            for name in &pragma.arguments {
+                self.set_level(name, level)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Vérifie les fonctions passées en paramètre et renvoie toutes les violations
+    /// Les suppressions de `allow_in` sont appliquées puis oubliées.
+    pub fn check<'a, I>(&mut self, functions: I) -> Vec<Lint>
+        where
+            I: IntoIterator<Item = &'a FunctionDeclaration>,
+    {
+        let mut phase = Phase::begin("lint");
+        let functions: Vec<_> = functions.into_iter().collect();
+        phase.set_items(functions.len());
+        let mut lints = Vec::new();
+        {
+            let mut suppressions = Suppressions::new(&self.allowed);
+            for function in &functions {
+                suppressions.visit_function(function);
+            }
+
+            for &mut (ref mut rule, level) in &mut self.rules {
+                if level == Level::Allow {
+                    continue;
+                }
+                for function in &functions {
+                    rule.visit_function(function);
+                }
+                let level = if self.deny_warnings && level == Level::Warn {
+                    Level::Deny
+                }
+                else {
+                    level
+                };
+                let name = rule.name();
+                trace_event!("règle '{}' vérifiée au niveau {:?}", name, level);
+                lints.extend(rule.take_messages()
+                    .into_iter()
+                    .filter(|message| !suppressions.is_allowed(name, message))
+                    .map(|message| Lint {
+                        rule: name,
+                        level,
+                        message: message.text,
+                        suggestion: message.suggestion,
+                    }));
+            }
+        }
+        self.allowed.clear();
+        lints
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new()
+    }
+}
+
+/// Les règles désactivées pour chaque énoncé, incluant celles d'un énoncé englobant
+struct Suppressions<'a> {
+    allowed: &'a [(StatementId, Vec<String>)],
+    /// Les règles désactivées pour l'énoncé en cours de visite
+    active: Vec<&'a str>,
+    statements: HashMap<StatementId, Vec<&'a str>>,
+}
+
+impl<'a> Suppressions<'a> {
+    fn new(allowed: &'a [(StatementId, Vec<String>)]) -> Self {
+        Suppressions { allowed, active: Vec::new(), statements: HashMap::new() }
+    }
+
+    /// Si la règle est désactivée pour l'énoncé du message
+    fn is_allowed(&self, rule: &str, message: &Message) -> bool {
+        message.statement
+            .and_then(|id| self.statements.get(&id))
+            .map_or(false, |rules| rules.contains(&rule))
+    }
+}
+
+impl<'a> Visitor for Suppressions<'a> {
+    fn visit_statement(&mut self, stmt: &Statement) {
+        let id = StatementId::of(stmt);
+        let depth = self.active.len();
+        let allowed = self.allowed;
+        for &(target, ref rules) in allowed {
+            if target == id {
+                self.active.extend(rules.iter().map(String::as_str));
+            }
+        }
+        if !self.active.is_empty() {
+            self.statements.insert(id, self.active.clone());
+        }
+        visit::walk_statement(self, stmt);
+        self.active.truncate(depth);
+    }
+}
+
+/// Extrait les noms de règles d'un commentaire de suppression
+/// `/* nb-lint: allow(a, b) */` renvoie `["a", "b"]`
+fn parse_suppression(comment: &str) -> Vec<String> {
+    let comment = comment
+        .trim()
+        .trim_left_matches("/*")
+        .trim_right_matches("*/")
+        .trim();
+    let rest = match comment.find("nb-lint:") {
+        Some(idx) => comment[idx + "nb-lint:".len()..].trim_left(),
+        None => return Vec::new(),
+    };
+    if !rest.starts_with("allow(") {
+        return Vec::new();
+    }
+    match rest.find(')') {
+        Some(end) => rest["allow(".len()..end]
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,281 @@
+//! Conventions de nommage configurables
+use ast::*;
+use lint::{Message, Rule, StatementId, Suggestion};
+use token::Keyword;
+use visit::{self, Visitor};
+
+/// Une convention de casse pour les noms
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Case {
+    /// `nom_de_variable`
+    Snake,
+    /// `NOM_DE_CONSTANTE`
+    ScreamingSnake,
+    /// `nomDeVariable`
+    Camel,
+    /// `NomDeType`
+    Pascal,
+}
+
+impl Case {
+    /// Renvoie si le nom respecte la convention
+    /// Les '_' au début et le '?' à la fin d'un identifiant sont ignorés
+    pub fn matches(&self, name: &str) -> bool {
+        self.convert(name) == name
+    }
+
+    /// Convertit un nom vers la convention
+    pub fn convert(&self, name: &str) -> String {
+        let prefix_len = name.len() - name.trim_left_matches('_').len();
+        let (prefix, rest) = name.split_at(prefix_len);
+        let core = rest.trim_right_matches('?');
+        let suffix = &rest[core.len()..];
+
+        let words = split_words(core);
+        let converted = match *self {
+            Case::Snake => words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join("_"),
+            Case::ScreamingSnake => {
+                words.iter().map(|word| word.to_uppercase()).collect::<Vec<_>>().join("_")
+            },
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+                .collect(),
+            Case::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        };
+
+        format!("{}{}{}", prefix, converted, suffix)
+    }
+}
+
+/// Sépare un nom en mots, soit aux '_' soit à une transition minuscule-majuscule
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            if !current.is_empty() {
+                words.push(current);
+                current = String::new();
+            }
+            previous_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(current);
+            current = String::new();
+        }
+        previous_lower = ch.is_lowercase() || ch.is_numeric();
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Met la première lettre en majuscule et les suivantes en minuscule
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// La convention de casse attendue pour chaque sorte de nom
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NamingPolicy {
+    pub functions: Case,
+    pub variables: Case,
+    pub constants: Case,
+    /// Sera appliqué aux déclarations de `struct` lorsqu'elles existeront
+    pub types: Case,
+}
+
+impl Default for NamingPolicy {
+    fn default() -> Self {
+        NamingPolicy {
+            functions: Case::Snake,
+            variables: Case::Snake,
+            constants: Case::ScreamingSnake,
+            types: Case::Pascal,
+        }
+    }
+}
+
+/// Les noms de fonctions, de paramètres, de variables et de constantes doivent
+/// respecter la `NamingPolicy` configurée
+#[derive(Debug, Default)]
+pub struct NamingConvention {
+    policy: NamingPolicy,
+    messages: Vec<Message>,
+}
+
+impl NamingConvention {
+    pub fn new(policy: NamingPolicy) -> Self {
+        NamingConvention { policy, messages: Vec::new() }
+    }
+
+    /// Vérifie un nom déclaré par `stmt`, ou par la signature de la fonction si `None`
+    fn check(&mut self, kind: &str, case: Case, name: &str, stmt: Option<&Statement>) {
+        if !case.matches(name) {
+            let replacement = case.convert(name);
+            self.messages.push(Message {
+                text: format!(
+                    "Le nom de {} '{}' ne respecte pas la convention {:?}, utiliser '{}'",
+                    kind, name, case, replacement,
+                ),
+                suggestion: Some(Suggestion { original: name.to_owned(), replacement }),
+                statement: stmt.map(StatementId::of),
+            });
+        }
+    }
+}
+
+impl Visitor for NamingConvention {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        let policy = self.policy;
+        self.check("fonction", policy.functions, &function.identifier, None);
+        for param in &function.parameters {
+            self.check("paramètre", policy.variables, &param.name, None);
+        }
+        visit::walk_block(self, &function.body);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match *stmt {
+            Statement::VariableDeclaration(ref decl) | Statement::LetElse(ref decl, _) => {
+                let policy = self.policy;
+                match decl.token {
+                    Keyword::Const => {
+                        self.check("constante", policy.constants, &decl.ident.name, Some(stmt))
+                    },
+                    _ => self.check("variable", policy.variables, &decl.ident.name, Some(stmt)),
+                }
+            },
+            _ => {},
+        }
+        visit::walk_statement(self, stmt);
+    }
+}
+
+impl Rule for NamingConvention {
+    fn name(&self) -> &'static str {
+        "naming_convention"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edits::TextEdit;
+    use lint::Linter;
+    use token::{Position, Span, Token, TokenType};
+
+    #[test]
+    fn convert() {
+        assert_eq!("est_pair?", Case::Snake.convert("estPair?"));
+        assert_eq!("_valeur_max", Case::Snake.convert("_ValeurMax"));
+        assert_eq!("VALEUR_MAX", Case::ScreamingSnake.convert("valeurMax"));
+        assert_eq!("valeurMax", Case::Camel.convert("valeur_max"));
+        assert_eq!("ValeurMax", Case::Pascal.convert("VALEUR_MAX"));
+        assert!(Case::Snake.matches("x1_y2"));
+        assert!(!Case::Pascal.matches("valeur"));
+    }
+
+    #[test]
+    fn configured_policy() {
+        let fun = FunctionDeclaration {
+            identifier: "calculer_total".to_string(),
+            parameters: vec![],
+            body: box Block::new(vec![
+                VariableDeclaration {
+                    token: Keyword::Const,
+                    ident: Variable {
+                        name: "taux".to_string(),
+                        category: Type { name: "int".to_string() },
+                    },
+                    value: box Literal::from(Number::Int(5)).into(),
+                }.into(),
+                Statement::LetElse(
+                    VariableDeclaration {
+                        token: Keyword::Let,
+                        ident: Variable {
+                            name: "valeurLue".to_string(),
+                            category: Type { name: "int".to_string() },
+                        },
+                        value: box Expression::Identifier("entree".to_string()),
+                    },
+                    box Block::new(vec![Statement::Return(None)]),
+                ),
+            ]),
+            return_type: Type { name: "int".to_string() },
+        };
+        let policy = NamingPolicy { functions: Case::Camel, ..NamingPolicy::default() };
+        let mut linter = Linter::with_rules(vec![box NamingConvention::new(policy)]);
+
+        let suggestions: Vec<_> = linter
+            .check(Some(&fun))
+            .into_iter()
+            .filter_map(|lint| lint.suggestion)
+            .collect();
+
+        assert_eq!(vec![
+            Suggestion { original: "calculer_total".to_string(), replacement: "calculerTotal".to_string() },
+            Suggestion { original: "taux".to_string(), replacement: "TAUX".to_string() },
+            Suggestion { original: "valeurLue".to_string(), replacement: "valeur_lue".to_string() },
+        ], suggestions);
+    }
+
+    #[test]
+    fn suggestion_fix() {
+        let token = |token_type, line, begin, end| Token::new(
+            token_type,
+            Span::new(Position::new(line, begin), Position::new(line, end)).into(),
+        );
+        let identifier = |name: &str, line, begin, end| {
+            token(TokenType::Identifier(name.to_string()), line, begin, end)
+        };
+        // `autre = monNombre; let monNombre = autre; monNombre = 1;`
+        let tokens = vec![
+            identifier("autre", 1, 1, 5),
+            token(TokenType::Eq, 1, 7, 7),
+            identifier("monNombre", 1, 9, 17),
+            token(TokenType::Semicolon, 1, 18, 18),
+            token(TokenType::Keyword(Keyword::Let), 2, 1, 3),
+            identifier("monNombre", 2, 5, 13),
+            token(TokenType::Eq, 2, 15, 15),
+            identifier("autre", 2, 17, 21),
+            token(TokenType::Semicolon, 2, 22, 22),
+            identifier("monNombre", 3, 1, 9),
+        ];
+
+        let suggestion = Suggestion {
+            original: "monNombre".to_string(),
+            replacement: "mon_nombre".to_string(),
+        };
+        assert_eq!(
+            Some(TextEdit::new(Span::new(Position::new(2, 5), Position::new(2, 13)), "mon_nombre")),
+            suggestion.fix(&tokens)
+        );
+
+        // un paramètre est aussi une déclaration
+        let tokens = vec![
+            token(TokenType::Lparen, 1, 6, 6),
+            identifier("monNombre", 1, 7, 15),
+            token(TokenType::Colon, 1, 16, 16),
+        ];
+        assert_eq!(
+            Some(TextEdit::new(Span::new(Position::new(1, 7), Position::new(1, 15)), "mon_nombre")),
+            suggestion.fix(&tokens)
+        );
+        assert_eq!(None, suggestion.fix(&tokens[1..]));
+    }
+}
@@ -0,0 +1,126 @@
+//! Configuration des niveaux du linter depuis la ligne de commande
+use lint::{Level, Linter, UnknownRule};
+
+use std::result;
+
+/// Un type spécialisé pour les erreurs de lecture des options
+pub type OResult<T> = result::Result<T, Error>;
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// Une option `-W`, `-A` ou `-D` n'est pas suivie d'un nom de règle
+    #[fail(display = "L'option '{}' doit être suivie du nom d'une règle", 0)]
+    MissingRuleName(String),
+}
+
+/// Les niveaux demandés avec `-W nom`, `-A nom`, `-D nom` et `--deny-warnings`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LintOptions {
+    /// Les niveaux dans l'ordre où ils ont été donnés, le dernier l'emporte
+    pub levels: Vec<(String, Level)>,
+    /// Toutes les règles `Warn` deviennent `Deny`
+    pub deny_warnings: bool,
+}
+
+impl LintOptions {
+    /// Lit les options du linter parmi les arguments
+    /// Le nom de la règle peut être collé à l'option (`-Wnom`) ou être l'argument suivant.
+    /// Les arguments qui ne concernent pas le linter sont renvoyés dans l'ordre.
+    pub fn parse_args<I, S>(args: I) -> OResult<(LintOptions, Vec<String>)>
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+    {
+        let mut options = LintOptions::default();
+        let mut rest = Vec::new();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            if arg == "--deny-warnings" {
+                options.deny_warnings = true;
+                continue;
+            }
+
+            let level = match arg.get(..2) {
+                Some("-W") => Level::Warn,
+                Some("-A") => Level::Allow,
+                Some("-D") => Level::Deny,
+                _ => {
+                    rest.push(arg);
+                    continue;
+                },
+            };
+            let name = if arg.len() > 2 {
+                arg[2..].to_owned()
+            }
+            else {
+                args.next().ok_or_else(|| Error::MissingRuleName(arg.clone()))?
+            };
+            options.levels.push((name, level));
+        }
+
+        Ok((options, rest))
+    }
+}
+
+impl Linter {
+    /// Applique les options au linter
+    /// Les règles inconnues sont ignorées et renvoyées pour être signalées à l'utilisateur.
+    pub fn apply_options(&mut self, options: &LintOptions) -> Vec<UnknownRule> {
+        if options.deny_warnings {
+            self.deny_warnings = true;
+        }
+        options
+            .levels
+            .iter()
+            .filter_map(|&(ref name, level)| self.set_level(name, level).err())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{Block, FunctionDeclaration, Type};
+
+    #[test]
+    fn parse_args() {
+        let args = vec!["-W", "empty_block", "fichier.nb", "-Aunused_parameter", "--deny-warnings"];
+
+        assert_eq!(Ok((
+            LintOptions {
+                levels: vec![
+                    ("empty_block".to_string(), Level::Warn),
+                    ("unused_parameter".to_string(), Level::Allow),
+                ],
+                deny_warnings: true,
+            },
+            vec!["fichier.nb".to_string()],
+        )), LintOptions::parse_args(args));
+
+        assert_eq!(
+            Err(Error::MissingRuleName("-D".to_string())),
+            LintOptions::parse_args(vec!["-D"])
+        );
+    }
+
+    #[test]
+    fn apply_options() {
+        let fun = FunctionDeclaration {
+            identifier: "f".to_string(),
+            parameters: vec![],
+            body: box Block::new(vec![]),
+            return_type: Type { name: "int".to_string() },
+        };
+        let (options, _) = LintOptions::parse_args(vec![
+            "-A", "inconnue", "-D", "naming_convention", "--deny-warnings",
+        ]).unwrap();
+        let mut linter = Linter::new();
+
+        assert_eq!(vec![UnknownRule("inconnue".to_string())], linter.apply_options(&options));
+        assert_eq!(Some(Level::Deny), linter.level("naming_convention"));
+
+        let levels: Vec<_> = linter.check(Some(&fun)).into_iter().map(|lint| lint.level).collect();
+        assert_eq!(vec![Level::Deny], levels);
+    }
+}
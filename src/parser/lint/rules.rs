@@ -0,0 +1,516 @@
+//! Les règles incluses avec le linter
+use ast::*;
+use lint::{Level, Message, Rule, StatementId};
+use token::Keyword;
+use visit::{self, Visitor};
+
+use std::collections::HashSet;
+use std::mem;
+
+/// Une variable déclarée porte le même nom qu'une variable ou un paramètre
+/// d'une portée englobante
+#[derive(Debug, Default)]
+pub struct ShadowedVariable {
+    scopes: Vec<HashSet<String>>,
+    messages: Vec<Message>,
+}
+
+impl Visitor for ShadowedVariable {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        let params = function.parameters.iter().map(|param| param.name.clone()).collect();
+        self.scopes.push(params);
+        visit::walk_block(self, &function.body);
+        self.scopes.pop();
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        self.scopes.push(HashSet::new());
+        visit::walk_block(self, block);
+        self.scopes.pop();
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        visit::walk_statement(self, stmt);
+        let declaration = match *stmt {
+            Statement::VariableDeclaration(ref decl)
+            | Statement::LetElse(ref decl, _) => Some(decl),
+            _ => None,
+        };
+        if let Some(decl) = declaration {
+            let name = &decl.ident.name;
+            if self.scopes.iter().any(|scope| scope.contains(name)) {
+                self.messages.push(
+                    Message::from(format!("La variable '{}' masque une variable précédente", name))
+                        .at(stmt),
+                );
+            }
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert(name.clone());
+            }
+        }
+    }
+}
+
+impl Rule for ShadowedVariable {
+    fn name(&self) -> &'static str {
+        "shadowed_variable"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+/// La condition d'une clause ou d'une boucle est une valeur littérale
+#[derive(Debug, Default)]
+pub struct ConstantCondition {
+    messages: Vec<Message>,
+}
+
+impl Visitor for ConstantCondition {
+    fn visit_statement(&mut self, stmt: &Statement) {
+        let condition = match *stmt {
+            Statement::Conditional(ref cond) => cond.condition.as_ref(),
+            Statement::Loop(ref looping) => looping.condition.as_ref(),
+            _ => None,
+        };
+        if let Some(condition) = condition {
+            if let Expression::Literal(ref lit) = **condition {
+                self.messages.push(
+                    Message::from(format!("La condition '{}' est constante", lit)).at(stmt),
+                );
+            }
+        }
+        visit::walk_statement(self, stmt);
+    }
+}
+
+impl Rule for ConstantCondition {
+    fn name(&self) -> &'static str {
+        "constant_condition"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+/// Le corps d'une fonction, d'une clause ou d'une boucle est vide
+#[derive(Debug, Default)]
+pub struct EmptyBlock {
+    messages: Vec<Message>,
+}
+
+impl Visitor for EmptyBlock {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        if function.body.statements().is_empty() {
+            self.messages.push(format!("La fonction '{}' est vide", function.identifier).into());
+        }
+        visit::walk_function(self, function);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        let (keyword, body) = match *stmt {
+            Statement::Conditional(ref cond) => (cond.token, &cond.body),
+            Statement::Loop(ref looping) => (looping.token, &looping.body),
+            _ => return visit::walk_statement(self, stmt),
+        };
+        if body.statements().is_empty() {
+            let text = format!("Le bloc du '{}' est vide", keyword);
+            self.messages.push(Message::from(text).at(stmt));
+        }
+        visit::walk_statement(self, stmt);
+    }
+}
+
+impl Rule for EmptyBlock {
+    fn name(&self) -> &'static str {
+        "empty_block"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+/// Un paramètre de fonction n'est jamais utilisé
+/// Les paramètres dont le nom débute par '_' sont ignorés
+#[derive(Debug, Default)]
+pub struct UnusedParameter {
+    used: HashSet<String>,
+    messages: Vec<Message>,
+}
+
+impl Visitor for UnusedParameter {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        self.used.clear();
+        visit::walk_block(self, &function.body);
+        for param in &function.parameters {
+            if !param.name.starts_with('_') && !self.used.contains(&param.name) {
+                self.messages.push(format!(
+                    "Le paramètre '{}' de la fonction '{}' n'est pas utilisé",
+                    param.name, function.identifier,
+                ).into());
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        if let Expression::Identifier(ref name) = *expr {
+            self.used.insert(name.clone());
+        }
+        visit::walk_expression(self, expr);
+    }
+}
+
+impl Rule for UnusedParameter {
+    fn name(&self) -> &'static str {
+        "unused_parameter"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+/// Une relation d'ordre dont un opérande est elle-même une relation d'ordre: `a < b < c`
+/// compare le booléen `a < b` à `c`, ce qui n'est presque jamais l'intention
+/// Les deux opérandes sont vérifiés, l'AST ne conservant pas les parenthèses de
+/// `a < (b < c)`. L'égalité n'est pas concernée: `a < b == drapeau` compare deux
+/// booléens, ce qui est valide.
+#[derive(Debug, Default)]
+pub struct ChainedComparison {
+    /// L'énoncé contenant l'expression visitée
+    statement: Option<StatementId>,
+    messages: Vec<Message>,
+}
+
+fn is_ordering(op: &BinaryOperator) -> bool {
+    use ast::BinaryOperator::*;
+    match *op {
+        Lower | LowerOrEqual | Greater | GreaterOrEqual => true,
+        _ => false,
+    }
+}
+
+impl ChainedComparison {
+    /// Signale `a first b` enchaîné à `b second c`
+    fn report(
+        &mut self,
+        a: &Expression,
+        first: &BinaryOperator,
+        b: &Expression,
+        second: &BinaryOperator,
+        c: &Expression,
+    ) {
+        let text = format!(
+            "Les comparaisons ne peuvent être enchaînées, utiliser '{a} {first} {b} && \
+             {b} {second} {c}'",
+            a = a, first = first, b = b, second = second, c = c,
+        );
+        self.messages.push(Message { statement: self.statement, ..text.into() });
+    }
+}
+
+impl Visitor for ChainedComparison {
+    fn visit_statement(&mut self, stmt: &Statement) {
+        let enclosing = mem::replace(&mut self.statement, Some(StatementId::of(stmt)));
+        visit::walk_statement(self, stmt);
+        self.statement = enclosing;
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        if let Expression::BinaryExpression(ref lhs, ref op, ref rhs) = *expr {
+            if is_ordering(op) {
+                if let Expression::BinaryExpression(ref a, ref inner, ref b) = **lhs {
+                    if is_ordering(inner) {
+                        self.report(a, inner, b, op, rhs);
+                    }
+                }
+                if let Expression::BinaryExpression(ref b, ref inner, ref c) = **rhs {
+                    if is_ordering(inner) {
+                        self.report(lhs, op, b, inner, c);
+                    }
+                }
+            }
+        }
+        visit::walk_expression(self, expr);
+    }
+}
+
+impl Rule for ChainedComparison {
+    fn name(&self) -> &'static str {
+        "chained_comparison"
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+/// Une fonction dont le nom se termine par '?' est un prédicat et doit renvoyer un `bool`
+/// Cette règle est optionnelle et doit être activée explicitement
+#[derive(Debug, Default)]
+pub struct PredicateReturnType {
+    messages: Vec<Message>,
+}
+
+impl Visitor for PredicateReturnType {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        if function.identifier.ends_with('?') && function.return_type.name != "bool" {
+            self.messages.push(format!(
+                "Le prédicat '{}' doit renvoyer 'bool' plutôt que '{}'",
+                function.identifier, function.return_type,
+            ).into());
+        }
+        visit::walk_function(self, function);
+    }
+}
+
+impl Rule for PredicateReturnType {
+    fn name(&self) -> &'static str {
+        "predicate_return_type"
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Allow
+    }
+
+    fn messages(&mut self) -> &mut Vec<Message> {
+        &mut self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lexer::Lexer;
+    use locale::Locale;
+    use lint::{Lint, Linter, UnknownRule};
+
+    fn var(name: &str) -> Variable {
+        Variable { name: name.to_string(), category: Type { name: "int".to_string() } }
+    }
+
+    fn function(name: &str, params: &[&str], body: Vec<Statement>) -> FunctionDeclaration {
+        FunctionDeclaration {
+            identifier: name.to_string(),
+            parameters: params.iter().map(|param| var(param)).collect(),
+            body: box Block::new(body),
+            return_type: Type { name: "int".to_string() },
+        }
+    }
+
+    fn declaration(name: &str, value: Expression) -> Statement {
+        VariableDeclaration {
+            token: Keyword::Let,
+            ident: var(name),
+            value: box value,
+        }.into()
+    }
+
+    fn messages(linter: &mut Linter, function: &FunctionDeclaration) -> Vec<(&'static str, String)> {
+        linter.check(Some(function)).into_iter().map(|lint| (lint.rule, lint.message)).collect()
+    }
+
+    #[test]
+    fn clean_function() {
+        let fun = function("somme", &["a", "b"], vec![
+            Statement::Return(Some(box Expression::BinaryExpression(
+                box "a".into(),
+                BinaryOperator::Plus,
+                box "b".into(),
+            ))),
+        ]);
+
+        assert_eq!(Vec::<Lint>::new(), Linter::new().check(Some(&fun)));
+    }
+
+    #[test]
+    fn shadowed_variable() {
+        let fun = function("f", &["x"], vec![
+            declaration("y", "x".into()),
+            ConditionalStatement {
+                token: Keyword::If,
+                condition: Some(box "y".into()),
+                body: box Block::new(vec![declaration("y", "x".into())]),
+            }.into(),
+            declaration("x", "y".into()),
+        ]);
+
+        assert_eq!(vec![
+            ("shadowed_variable", "La variable 'y' masque une variable précédente".to_string()),
+            ("shadowed_variable", "La variable 'x' masque une variable précédente".to_string()),
+        ], messages(&mut Linter::new(), &fun));
+    }
+
+    #[test]
+    fn constant_condition_and_empty_block() {
+        let fun = function("f", &[], vec![
+            LoopStatement {
+                token: Keyword::While,
+                condition: Some(box Literal::Boolean(true).into()),
+                body: box Block::new(vec![]),
+            }.into(),
+        ]);
+
+        assert_eq!(vec![
+            ("constant_condition", "La condition 'true' est constante".to_string()),
+            ("empty_block", "Le bloc du 'while' est vide".to_string()),
+        ], messages(&mut Linter::new(), &fun));
+    }
+
+    #[test]
+    fn chained_comparison() {
+        let comparison = |lhs, op, rhs| Expression::BinaryExpression(box lhs, op, box rhs);
+        let fun = function("f", &["a", "b", "c"], vec![
+            Statement::Return(Some(box comparison(
+                comparison("a".into(), BinaryOperator::Lower, "b".into()),
+                BinaryOperator::LowerOrEqual,
+                "c".into(),
+            ))),
+            // `(a + b) < c` n'est pas un enchaînement
+            Statement::Return(Some(box comparison(
+                comparison("a".into(), BinaryOperator::Plus, "b".into()),
+                BinaryOperator::Lower,
+                "c".into(),
+            ))),
+            // `(a < b) == c` compare deux booléens
+            Statement::Return(Some(box comparison(
+                comparison("a".into(), BinaryOperator::Lower, "b".into()),
+                BinaryOperator::Equality,
+                "c".into(),
+            ))),
+            Statement::Return(Some(box comparison(
+                "a".into(),
+                BinaryOperator::Greater,
+                comparison("b".into(), BinaryOperator::GreaterOrEqual, "c".into()),
+            ))),
+        ]);
+
+        let lints = Linter::new().check(Some(&fun));
+        let lint = |message: &str| Lint {
+            rule: "chained_comparison",
+            level: Level::Warn,
+            message: message.to_string(),
+            suggestion: None,
+        };
+        assert_eq!(vec![
+            lint("Les comparaisons ne peuvent être enchaînées, utiliser '(a) < (b) && (b) <= (c)'"),
+            lint("Les comparaisons ne peuvent être enchaînées, utiliser '(a) > (b) && (b) >= (c)'"),
+        ], lints);
+    }
+
+    #[test]
+    fn predicate_return_type() {
+        let mut predicate = function("vide?", &[], vec![]);
+        let mut linter = Linter::with_rules(vec![box PredicateReturnType::default()]);
+
+        assert_eq!(Vec::<Lint>::new(), linter.check(Some(&predicate)));
+
+        linter.set_level("predicate_return_type", Level::Warn).unwrap();
+        assert_eq!(vec![
+            ("predicate_return_type", "Le prédicat 'vide?' doit renvoyer 'bool' plutôt que 'int'"
+                .to_string()),
+        ], messages(&mut linter, &predicate));
+
+        predicate.return_type = Type { name: "bool".to_string() };
+        assert_eq!(Vec::<Lint>::new(), linter.check(Some(&predicate)));
+    }
+
+    #[test]
+    fn naming_convention_and_unused_parameter() {
+        let fun = function("estPair?", &["Valeur", "_ignore"], vec![
+            declaration("est_pair", Literal::Boolean(false).into()),
+            Statement::Return(Some(box "est_pair".into())),
+        ]);
+
+        assert_eq!(vec![
+            ("naming_convention",
+             "Le nom de fonction 'estPair?' ne respecte pas la convention Snake, utiliser 'est_pair?'"
+                 .to_string()),
+            ("naming_convention",
+             "Le nom de paramètre 'Valeur' ne respecte pas la convention Snake, utiliser 'valeur'"
+                 .to_string()),
+            ("unused_parameter",
+             "Le paramètre 'Valeur' de la fonction 'estPair?' n'est pas utilisé".to_string()),
+        ], messages(&mut Linter::new(), &fun));
+    }
+
+    #[test]
+    fn levels_and_suppression() {
+        let fun = function("f", &[], vec![]);
+        let mut linter = Linter::new();
+
+        assert_eq!(
+            Err(UnknownRule("inconnue".to_string())),
+            linter.set_level("inconnue", Level::Deny)
+        );
+
+        linter.set_level("empty_block", Level::Deny).unwrap();
+        assert_eq!(vec![Lint {
+            rule: "empty_block",
+            level: Level::Deny,
+            message: "La fonction 'f' est vide".to_string(),
+            suggestion: None,
+        }], linter.check(Some(&fun)));
+
+        linter.set_level("empty_block", Level::Allow).unwrap();
+        assert_eq!(Some(Level::Allow), linter.level("empty_block"));
+        assert_eq!(Vec::<Lint>::new(), linter.check(Some(&fun)));
+    }
+
+    #[test]
+    fn scoped_suppression() {
+        let clause = |token, condition: &str| -> Statement {
+            ConditionalStatement {
+                token,
+                condition: Some(box Expression::Identifier(condition.to_string())),
+                body: box Block::new(vec![]),
+            }.into()
+        };
+        let mut linter = Linter::new();
+
+        let rules = linter
+            .suppression_from_comment("/* nb-lint: allow(empty_block, naming_convention) */")
+            .unwrap();
+        assert_eq!(vec!["empty_block".to_string(), "naming_convention".to_string()], rules);
+        assert_eq!(Ok(Vec::new()), linter.suppression_from_comment("/* allow(x) */"));
+        assert_eq!(
+            Err(UnknownRule("inconnue".to_string())),
+            linter.suppression_from_comment("/* nb-lint: allow(inconnue) */")
+        );
+
+        let fun = function("f", &["a"], vec![
+            clause(Keyword::If, "a"),
+            clause(Keyword::While, "a"),
+        ]);
+        linter.allow_in(&fun.body.statements()[0], rules);
+
+        // seul l'énoncé suivant le commentaire est concerné, et le niveau est inchangé
+        assert_eq!(vec![
+            ("empty_block", "Le bloc du 'while' est vide".to_string()),
+        ], messages(&mut linter, &fun));
+        assert_eq!(Some(Level::Warn), linter.level("empty_block"));
+    }
+
+    #[test]
+    fn level_labels() {
+        assert_eq!("permis", Level::Allow.to_string());
+        assert_eq!("avertissement", Level::Warn.to_string());
+        assert_eq!("error", Level::Deny.label(Locale::English));
+        assert_eq!("allowed", Level::Allow.label(Locale::English));
+    }
+
+    #[test]
+    fn file_pragmas() {
+        let mut lexer = Lexer::new("#![deny(empty_block)]\n#![allow(unused_parameter)]\n");
+        lexer.read_token().unwrap();
+        let mut linter = Linter::new();
+
+        linter.configure(lexer.file_attributes()).unwrap();
+        assert_eq!(Some(Level::Deny), linter.level("empty_block"));
+        assert_eq!(Some(Level::Allow), linter.level("unused_parameter"));
+        assert_eq!(Some(Level::Warn), linter.level("constant_condition"));
+    }
+}
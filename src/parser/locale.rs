@@ -0,0 +1,25 @@
+/// La langue des messages destinés à l'utilisateur
+/// Les codes des diagnostics restent les mêmes peu importe la langue choisie.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Locale {
+    French,
+    English,
+}
+
+impl Locale {
+    /// Trouve la langue correspondant à une étiquette comme `fr`, `fr-CA` ou `en_US`
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(|ch| ch == '-' || ch == '_').next().unwrap_or("");
+        match language.to_lowercase().as_str() {
+            "fr" => Some(Locale::French),
+            "en" => Some(Locale::English),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::French
+    }
+}
@@ -0,0 +1,54 @@
+//! Journalisation des étapes du compilateur, activée par la feature `logging`
+//! Les événements sont émis avec le crate `log`; le programme hôte choisit où ils vont
+//! (`env_logger`, ...). Sans la feature, les macros de ce module ne font rien.
+
+use stats;
+
+use std::time::Instant;
+
+/// Émet un événement de niveau `debug`, seulement avec la feature `logging`
+macro_rules! debug_event {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        debug!(target: "nb_parser", $($arg)*);
+    }};
+}
+
+/// Émet un événement de niveau `trace`, seulement avec la feature `logging`
+macro_rules! trace_event {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "logging")]
+        trace!(target: "nb_parser", $($arg)*);
+    }};
+}
+
+/// Une étape du compilateur: son début est journalisé à la construction et sa fin,
+/// avec sa durée, lorsque la valeur est détruite
+/// La fin est aussi comptée dans les statistiques en cours de collecte, voir `stats`.
+pub(crate) struct Phase {
+    name: &'static str,
+    begin: Instant,
+    /// Le nombre d'éléments traités par l'étape
+    items: usize,
+}
+
+impl Phase {
+    /// Débute une étape traitant un seul élément, voir `set_items`
+    pub(crate) fn begin(name: &'static str) -> Self {
+        debug_event!("début de l'étape '{}'", name);
+        Phase { name, begin: Instant::now(), items: 1 }
+    }
+
+    /// Le nombre d'éléments traités, lorsque l'étape en traite plus d'un
+    pub(crate) fn set_items(&mut self, items: usize) {
+        self.items = items;
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        let elapsed = self.begin.elapsed();
+        debug_event!("fin de l'étape '{}' ({:?})", self.name, elapsed);
+        stats::record(self.name, elapsed, self.items);
+    }
+}
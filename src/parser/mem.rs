@@ -0,0 +1,219 @@
+//! Estimation de la mémoire occupée par les lexèmes et l'AST.
+//! Les estimations incluent la mémoire allouée sur le tas (chaînes de caractères,
+//! vecteurs, boîtes) mais pas la surcharge de l'allocateur.
+use ast::*;
+use token::{self, Token, TokenType};
+
+use std::mem;
+
+pub trait MemUsage {
+    /// La mémoire allouée sur le tas par la valeur et ses enfants
+    fn heap_usage(&self) -> usize;
+
+    /// La mémoire totale: la taille de la valeur elle-même et ce qu'elle alloue
+    fn mem_usage(&self) -> usize {
+        mem::size_of_val(self) + self.heap_usage()
+    }
+}
+
+impl MemUsage for String {
+    fn heap_usage(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: MemUsage> MemUsage for Vec<T> {
+    fn heap_usage(&self) -> usize {
+        self.capacity() * mem::size_of::<T>() + self.iter().map(T::heap_usage).sum::<usize>()
+    }
+}
+
+impl<T: MemUsage> MemUsage for [T] {
+    fn heap_usage(&self) -> usize {
+        self.iter().map(T::heap_usage).sum()
+    }
+}
+
+impl<T: MemUsage> MemUsage for Box<T> {
+    fn heap_usage(&self) -> usize {
+        mem::size_of::<T>() + (**self).heap_usage()
+    }
+}
+
+impl<T: MemUsage> MemUsage for Option<T> {
+    fn heap_usage(&self) -> usize {
+        self.as_ref().map(T::heap_usage).unwrap_or(0)
+    }
+}
+
+impl MemUsage for Token {
+    fn heap_usage(&self) -> usize {
+        self.token_type().heap_usage()
+    }
+}
+
+impl MemUsage for TokenType {
+    fn heap_usage(&self) -> usize {
+        use token::TokenType::*;
+        match *self {
+            Illegal(ref st)
+            | Identifier(ref st)
+            | Comment(ref st)
+            | Whitespace(ref st)
+            | Literal(ref st) => st.heap_usage(),
+            Number(ref num) => num.heap_usage(),
+            _ => 0,
+        }
+    }
+}
+
+impl MemUsage for token::Number {
+    fn heap_usage(&self) -> usize {
+        use token::Number::*;
+        match *self {
+            Binary(ref st)
+            | Decimal(ref st)
+            | Float(ref st)
+            | Hexadecimal(ref st)
+            | Octal(ref st) => st.heap_usage(),
+        }
+    }
+}
+
+impl MemUsage for FunctionDeclaration {
+    fn heap_usage(&self) -> usize {
+        self.identifier.heap_usage()
+            + self.parameters.heap_usage()
+            + self.body.heap_usage()
+            + self.return_type.heap_usage()
+    }
+}
+
+impl MemUsage for Block {
+    /// La capacité du vecteur n'est pas exposée, seuls les énoncés présents sont comptés
+    fn heap_usage(&self) -> usize {
+        let statements = self.statements();
+        statements.len() * mem::size_of::<Statement>() + statements.heap_usage()
+    }
+}
+
+impl MemUsage for Statement {
+    fn heap_usage(&self) -> usize {
+        use ast::Statement::*;
+        match *self {
+            Assignment(ref var, ref expr) => var.heap_usage() + expr.heap_usage(),
+            Conditional(ref cond) => cond.condition.heap_usage() + cond.body.heap_usage(),
+            Loop(ref looping) => looping.condition.heap_usage() + looping.body.heap_usage(),
+            Expression(ref expr) => expr.heap_usage(),
+            Return(ref expr) => expr.heap_usage(),
+            VariableDeclaration(ref decl) => decl.ident.heap_usage() + decl.value.heap_usage(),
+            LetElse(ref decl, ref otherwise) => {
+                decl.ident.heap_usage() + decl.value.heap_usage() + otherwise.heap_usage()
+            },
+            Error(_) => 0,
+        }
+    }
+}
+
+impl MemUsage for Expression {
+    fn heap_usage(&self) -> usize {
+        use ast::Expression::*;
+        match *self {
+            Identifier(ref st) => st.heap_usage(),
+            Literal(ref lit) => lit.heap_usage(),
+            FunCall { ref target, ref arguments } => target.heap_usage() + arguments.heap_usage(),
+            BinaryExpression(ref lhs, _, ref rhs) => lhs.heap_usage() + rhs.heap_usage(),
+            UnaryExpression(ref expr, _) => expr.heap_usage(),
+            Error(_) => 0,
+        }
+    }
+}
+
+impl MemUsage for Literal {
+    fn heap_usage(&self) -> usize {
+        match *self {
+            Literal::Array(ref elements) => elements.heap_usage(),
+            Literal::String(ref st) => st.heap_usage(),
+            Literal::Number(_, ref text) => text.heap_usage(),
+            Literal::Boolean(_) => 0,
+        }
+    }
+}
+
+impl MemUsage for Variable {
+    fn heap_usage(&self) -> usize {
+        self.name.heap_usage() + self.category.heap_usage()
+    }
+}
+
+impl MemUsage for Type {
+    fn heap_usage(&self) -> usize {
+        self.name.heap_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::{Position, PositionOrSpan};
+
+    #[test]
+    fn tokens() {
+        let ident = String::with_capacity(16);
+        let mut tokens = Vec::with_capacity(4);
+        tokens.push(Token::new(TokenType::Identifier(ident), Position::new(1, 1).into()));
+        tokens.push(Token::new(TokenType::Semicolon, Position::new(1, 2).into()));
+
+        assert_eq!(16, tokens[0].heap_usage());
+        assert_eq!(0, tokens[1].heap_usage());
+        assert_eq!(
+            mem::size_of::<Vec<Token>>() + 4 * mem::size_of::<Token>() + 16,
+            tokens.mem_usage()
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        let expr = Expression::BinaryExpression(
+            box Expression::Identifier(String::with_capacity(8)),
+            BinaryOperator::Plus,
+            box Literal::from(Number::Int(1)).into(),
+        );
+
+        assert_eq!(mem::size_of::<Expression>() * 3 + 8, expr.mem_usage());
+    }
+
+    /// Budgets de taille des types les plus fréquents
+    /// Un changement qui fait grossir un de ces types (une gamme de plus, une nouvelle
+    /// variante avec un champ large) doit être délibéré: mettre le champ dans une `Box`
+    /// ou l'interner plutôt que d'augmenter le budget.
+    /// Chaque budget est la taille de la plus grande variante plus un mot pour le
+    /// discriminant. Les tailles exactes dépendent de la disposition choisie par rustc,
+    /// qui peut loger le discriminant dans une niche d'un champ (la capacité d'une
+    /// `String`, par exemple): elles ne sont donc pas comparées.
+    #[test]
+    fn size_budgets() {
+        let word = mem::size_of::<usize>();
+        assert_eq!(
+            mem::size_of::<TokenType>() + mem::size_of::<PositionOrSpan>(),
+            mem::size_of::<Token>()
+        );
+        // `Number`, qui contient le texte du nombre
+        assert!(mem::size_of::<TokenType>() <= mem::size_of::<token::Number>() + word);
+        // `FunCall`: le nom de la cible et les arguments
+        assert!(
+            mem::size_of::<Expression>()
+                <= mem::size_of::<String>() + mem::size_of::<Vec<Box<Expression>>>() + word
+        );
+        // une valeur et son texte original
+        assert!(
+            mem::size_of::<Literal>()
+                <= mem::size_of::<Number>() + mem::size_of::<Option<String>>() + word
+        );
+        // `LetElse`: une `VariableDeclaration` et une `Box<Block>`
+        assert!(
+            mem::size_of::<Statement>()
+                <= mem::size_of::<VariableDeclaration>() + mem::size_of::<Box<Block>>() + word
+        );
+    }
+}
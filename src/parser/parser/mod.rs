@@ -3,3 +3,5 @@ use phf;
 use ast;
 use token;
 use lexer::{Lexer, error::Error};
+
+pub mod precedence;
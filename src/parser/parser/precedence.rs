@@ -0,0 +1,251 @@
+//! Table de précédence des opérateurs binaires
+//!
+//! Le parseur utilisera cette table pour la montée de précédence (precedence climbing).
+//! Seuls les opérateurs s'y retrouvent: une parenthèse ou un autre symbole n'a pas de
+//! précédence et ne doit jamais être confondu avec un opérateur de faible précédence.
+//! `verify_precedence_table` garantit la cohérence de la table avec `BinaryOperator`;
+//! elle est vérifiée aux tests et, en mode debug, lors de la première consultation.
+
+use ast::{BinaryOperator, UnaryOperator};
+use token::{TokenCategory, TokenType};
+
+use std::sync::{Once, ONCE_INIT};
+
+/// La précédence d'un opérateur, de la plus faible à la plus forte
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Precedence {
+    Lowest,
+    /// `==`, `!=`
+    Equality,
+    /// `<`, `>`, `<=`, `>=`
+    Comparison,
+    /// `|`: plus forte que les comparaisons, donc `a | b == c` est `(a | b) == c`
+    BitOr,
+    /// `^^`
+    BitXor,
+    /// `&`
+    BitAnd,
+    /// `<<`, `>>`: `a << 1 < b` est `(a << 1) < b`, et `a << b + 1` est `a << (b + 1)`
+    Shift,
+    /// `+`, `-`
+    Sum,
+    /// `*`, `/`, `%`
+    Product,
+    /// `^`
+    Power,
+    /// Opérateurs unaires en position préfixe
+    Prefix,
+}
+
+/// Table des opérateurs binaires: le lexème, l'opérateur et sa précédence
+static BINARY_OPERATORS: &[(TokenType, BinaryOperator, Precedence)] = &[
+    (TokenType::EqEq, BinaryOperator::Equality, Precedence::Equality),
+    (TokenType::NotEq, BinaryOperator::NotEqual, Precedence::Equality),
+    (TokenType::Lt, BinaryOperator::Lower, Precedence::Comparison),
+    (TokenType::Gt, BinaryOperator::Greater, Precedence::Comparison),
+    (TokenType::LtEq, BinaryOperator::LowerOrEqual, Precedence::Comparison),
+    (TokenType::GtEq, BinaryOperator::GreaterOrEqual, Precedence::Comparison),
+    (TokenType::Or, BinaryOperator::BitOr, Precedence::BitOr),
+    (TokenType::Xor, BinaryOperator::BitXor, Precedence::BitXor),
+    (TokenType::And, BinaryOperator::BitAnd, Precedence::BitAnd),
+    (TokenType::Shl, BinaryOperator::ShiftLeft, Precedence::Shift),
+    (TokenType::Shr, BinaryOperator::ShiftRight, Precedence::Shift),
+    (TokenType::Plus, BinaryOperator::Plus, Precedence::Sum),
+    (TokenType::Minus, BinaryOperator::Minus, Precedence::Sum),
+    (TokenType::Multiplication, BinaryOperator::Multiplication, Precedence::Product),
+    (TokenType::Division, BinaryOperator::Division, Precedence::Product),
+    (TokenType::Modulo, BinaryOperator::Modulo, Precedence::Product),
+    (TokenType::Power, BinaryOperator::Power, Precedence::Power),
+];
+
+/// Table des opérateurs unaires en position préfixe
+static UNARY_OPERATORS: &[(TokenType, UnaryOperator)] = &[
+    (TokenType::Not, UnaryOperator::Not),
+    (TokenType::Minus, UnaryOperator::Negative),
+    (TokenType::Tilde, UnaryOperator::BitNot),
+];
+
+/// Les lexèmes pouvant être à la fois un opérateur unaire et binaire, comme `-`
+/// Le parseur les distingue selon leur position.
+/// `^` étant la puissance, le ou exclusif est `^^` plutôt que `~`, qui reste seulement
+/// la négation bit à bit.
+static AMBIGUOUS_OPERATORS: &[TokenType] = &[TokenType::Minus];
+
+/// Tous les opérateurs binaires
+/// L'ajout d'une variante à `BinaryOperator` rend `token_of` non-exhaustif, ce qui
+/// rappelle de l'ajouter ici et dans la table
+const ALL_BINARY_OPERATORS: &[BinaryOperator] = &[
+    BinaryOperator::BitAnd,
+    BinaryOperator::BitOr,
+    BinaryOperator::BitXor,
+    BinaryOperator::Division,
+    BinaryOperator::Equality,
+    BinaryOperator::Greater,
+    BinaryOperator::GreaterOrEqual,
+    BinaryOperator::Lower,
+    BinaryOperator::LowerOrEqual,
+    BinaryOperator::Minus,
+    BinaryOperator::Modulo,
+    BinaryOperator::Multiplication,
+    BinaryOperator::NotEqual,
+    BinaryOperator::Plus,
+    BinaryOperator::Power,
+    BinaryOperator::ShiftLeft,
+    BinaryOperator::ShiftRight,
+];
+
+/// L'opérateur binaire et sa précédence correspondant au lexème, s'il y en a un
+pub fn binary_operator(token: &TokenType) -> Option<(&'static BinaryOperator, Precedence)> {
+    debug_verify();
+    find_binary(token)
+}
+
+/// L'opérateur unaire correspondant au lexème, s'il y en a un
+pub fn unary_operator(token: &TokenType) -> Option<&'static UnaryOperator> {
+    debug_verify();
+    find_unary(token)
+}
+
+fn find_binary(token: &TokenType) -> Option<(&'static BinaryOperator, Precedence)> {
+    BINARY_OPERATORS.iter()
+        .find(|&&(ref tok, _, _)| tok == token)
+        .map(|&(_, ref op, precedence)| (op, precedence))
+}
+
+fn find_unary(token: &TokenType) -> Option<&'static UnaryOperator> {
+    UNARY_OPERATORS.iter()
+        .find(|&&(ref tok, _)| tok == token)
+        .map(|&(_, ref op)| op)
+}
+
+/// Vérifie les tables une seule fois, en mode debug seulement
+fn debug_verify() {
+    static VERIFIED: Once = ONCE_INIT;
+    if cfg!(debug_assertions) {
+        VERIFIED.call_once(|| debug_assert_eq!(Ok(()), verify_precedence_table()));
+    }
+}
+
+/// La précédence du lexème en position infixe, `Precedence::Lowest` s'il n'est pas
+/// un opérateur binaire
+pub fn precedence(token: &TokenType) -> Precedence {
+    binary_operator(token).map_or(Precedence::Lowest, |(_, precedence)| precedence)
+}
+
+/// Si l'opérateur est associatif à droite: `a ^ b ^ c` est `a ^ (b ^ c)`
+pub fn is_right_associative(op: &BinaryOperator) -> bool {
+    *op == BinaryOperator::Power
+}
+
+/// Le lexème d'un opérateur binaire
+fn token_of(op: &BinaryOperator) -> TokenType {
+    use ast::BinaryOperator::*;
+    match *op {
+        BitAnd => TokenType::And,
+        BitOr => TokenType::Or,
+        BitXor => TokenType::Xor,
+        Division => TokenType::Division,
+        Equality => TokenType::EqEq,
+        Greater => TokenType::Gt,
+        GreaterOrEqual => TokenType::GtEq,
+        Lower => TokenType::Lt,
+        LowerOrEqual => TokenType::LtEq,
+        Minus => TokenType::Minus,
+        Modulo => TokenType::Modulo,
+        Multiplication => TokenType::Multiplication,
+        NotEqual => TokenType::NotEq,
+        Plus => TokenType::Plus,
+        Power => TokenType::Power,
+        ShiftLeft => TokenType::Shl,
+        ShiftRight => TokenType::Shr,
+    }
+}
+
+/// Vérifie la cohérence des tables d'opérateurs:
+/// - chaque `BinaryOperator` a exactement une entrée, et chaque entrée correspond à son lexème
+/// - seuls des lexèmes de la catégorie `Operator` s'y trouvent, jamais une parenthèse
+/// - aucune entrée n'a `Precedence::Lowest` ou `Precedence::Prefix`
+/// - un lexème unaire n'est binaire que s'il est listé dans `AMBIGUOUS_OPERATORS`, et
+///   chaque lexème de `AMBIGUOUS_OPERATORS` est à la fois unaire et binaire
+pub(crate) fn verify_precedence_table() -> Result<(), String> {
+    for op in ALL_BINARY_OPERATORS {
+        let count = BINARY_OPERATORS.iter().filter(|&&(_, ref entry, _)| entry == op).count();
+        if count != 1 {
+            return Err(format!("{:?} a {} entrées dans la table de précédence", op, count));
+        }
+    }
+
+    for &(ref token, ref op, precedence) in BINARY_OPERATORS {
+        if !ALL_BINARY_OPERATORS.contains(op) || token_of(op) != *token {
+            return Err(format!("{:?} ne correspond pas au lexème {:?}", op, token));
+        }
+        if token.category() != TokenCategory::Operator {
+            return Err(format!("{:?} n'est pas un opérateur", token));
+        }
+        if precedence == Precedence::Lowest || precedence == Precedence::Prefix {
+            return Err(format!("{:?} a une précédence invalide: {:?}", op, precedence));
+        }
+    }
+
+    for &(ref token, _) in UNARY_OPERATORS {
+        if token.category() != TokenCategory::Operator {
+            return Err(format!("{:?} n'est pas un opérateur", token));
+        }
+        if find_binary(token).is_some() && !AMBIGUOUS_OPERATORS.contains(token) {
+            return Err(format!("{:?} est à la fois unaire et binaire", token));
+        }
+    }
+
+    for token in AMBIGUOUS_OPERATORS {
+        if find_binary(token).is_none() || find_unary(token).is_none() {
+            return Err(format!("{:?} n'est pas à la fois unaire et binaire", token));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_consistent() {
+        assert_eq!(Ok(()), verify_precedence_table());
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(
+            Some((&BinaryOperator::Plus, Precedence::Sum)),
+            binary_operator(&TokenType::Plus)
+        );
+        assert_eq!(Some(&UnaryOperator::Not), unary_operator(&TokenType::Not));
+        assert_eq!(None, binary_operator(&TokenType::Not));
+        assert_eq!(Precedence::Lowest, precedence(&TokenType::Lparen));
+        assert!(precedence(&TokenType::Multiplication) > precedence(&TokenType::Minus));
+        assert!(is_right_associative(&BinaryOperator::Power));
+
+        assert_eq!(
+            Some((&BinaryOperator::ShiftRight, Precedence::Shift)),
+            binary_operator(&TokenType::Shr)
+        );
+        assert!(precedence(&TokenType::Shl) > precedence(&TokenType::Lt));
+        assert!(precedence(&TokenType::Shl) < precedence(&TokenType::Plus));
+        assert!(!is_right_associative(&BinaryOperator::ShiftLeft));
+
+        assert_eq!(Some(&UnaryOperator::BitNot), unary_operator(&TokenType::Tilde));
+        assert_eq!(Some(&UnaryOperator::Negative), unary_operator(&TokenType::Minus));
+        assert_eq!(
+            Some((&BinaryOperator::BitXor, Precedence::BitXor)),
+            binary_operator(&TokenType::Xor)
+        );
+        assert_eq!(None, binary_operator(&TokenType::Tilde));
+        assert_eq!(None, binary_operator(&TokenType::AndAnd));
+        assert_eq!(None, binary_operator(&TokenType::OrOr));
+        assert!(precedence(&TokenType::Or) > precedence(&TokenType::EqEq));
+        assert!(precedence(&TokenType::Or) < precedence(&TokenType::Xor));
+        assert!(precedence(&TokenType::Xor) < precedence(&TokenType::And));
+        assert!(precedence(&TokenType::And) < precedence(&TokenType::Shl));
+    }
+}
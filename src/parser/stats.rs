@@ -0,0 +1,150 @@
+//! Statistiques des étapes du compilateur
+//! Chaque `logging::Phase` terminée pendant `CompilationStats::measure` y est comptée, ce
+//! qui permet aux outils et aux bancs d'essai d'obtenir la durée de chaque étape sans
+//! activer la journalisation.
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::time::Duration;
+
+thread_local! {
+    /// Les statistiques en cours de collecte sur ce fil d'exécution, s'il y en a
+    static CURRENT: RefCell<Option<CompilationStats>> = RefCell::new(None);
+}
+
+/// La durée d'une étape et le nombre d'éléments qu'elle a traités
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PhaseStats {
+    pub name: &'static str,
+    pub duration: Duration,
+    /// Le nombre d'éléments traités: lexèmes pour le lexer, fonctions pour les autres étapes
+    pub items: usize,
+    /// Le nombre de fois où l'étape a été exécutée
+    pub runs: usize,
+}
+
+/// Les statistiques d'une compilation, une entrée par étape dans l'ordre de première exécution
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompilationStats {
+    pub phases: Vec<PhaseStats>,
+}
+
+impl CompilationStats {
+    /// Exécute `f` et renvoie son résultat avec les statistiques des étapes qu'elle a
+    /// exécutées sur ce fil d'exécution
+    /// Les étapes d'un appel imbriqué ne sont comptées que par l'appel le plus imbriqué.
+    pub fn measure<F, T>(f: F) -> (T, CompilationStats)
+        where
+            F: FnOnce() -> T,
+    {
+        let enclosing = CURRENT.with(|current| {
+            mem::replace(&mut *current.borrow_mut(), Some(CompilationStats::default()))
+        });
+        let result = f();
+        let stats = CURRENT.with(|current| {
+            let mut current = current.borrow_mut();
+            let stats = current.take().unwrap_or_default();
+            *current = enclosing;
+            stats
+        });
+        (result, stats)
+    }
+
+    /// Les statistiques de l'étape nommée `name`, si elle a été exécutée
+    pub fn phase(&self, name: &str) -> Option<&PhaseStats> {
+        self.phases.iter().find(|phase| phase.name == name)
+    }
+
+    /// La durée totale des étapes
+    pub fn total(&self) -> Duration {
+        self.phases.iter().fold(Duration::new(0, 0), |total, phase| total + phase.duration)
+    }
+
+    fn add(&mut self, name: &'static str, duration: Duration, items: usize) {
+        match self.phases.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => {
+                phase.duration += duration;
+                phase.items += items;
+                phase.runs += 1;
+            },
+            None => self.phases.push(PhaseStats { name, duration, items, runs: 1 }),
+        }
+    }
+}
+
+/// Un tableau d'une ligne par étape, suivi du total
+impl fmt::Display for CompilationStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<12} {:>12} {:>10} {:>10}", "étape", "durée (ms)", "éléments", "appels")?;
+        for phase in &self.phases {
+            writeln!(
+                f,
+                "{:<12} {:>12.3} {:>10} {:>10}",
+                phase.name, millis(phase.duration), phase.items, phase.runs
+            )?;
+        }
+        writeln!(f, "{:<12} {:>12.3}", "total", millis(self.total()))
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0
+}
+
+/// Compte une étape terminée, si des statistiques sont en cours de collecte
+pub(crate) fn record(name: &'static str, duration: Duration, items: usize) {
+    CURRENT.with(|current| {
+        if let Some(ref mut stats) = *current.borrow_mut() {
+            stats.add(name, duration, items);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{Block, FunctionDeclaration, Type};
+    use desugar::desugar;
+    use lexer::Lexer;
+    use lint::Linter;
+
+    #[test]
+    fn measure_phases() {
+        let function = FunctionDeclaration {
+            identifier: "f".to_string(),
+            parameters: vec![],
+            body: box Block::new(vec![]),
+            return_type: Type { name: "void".to_string() },
+        };
+
+        let ((tokens, _), stats) = CompilationStats::measure(|| {
+            desugar(&function);
+            desugar(&function);
+            Linter::new().check(Some(&function));
+            Lexer::new("let x = 1;").tokenize_all()
+        });
+
+        let names: Vec<_> = stats.phases.iter().map(|phase| phase.name).collect();
+        assert_eq!(vec!["désucrage", "lint", "lexer"], names);
+        assert_eq!(tokens.len(), stats.phase("lexer").unwrap().items);
+        let desugaring = stats.phase("désucrage").unwrap();
+        assert_eq!((2, 2), (desugaring.items, desugaring.runs));
+        assert!(stats.total() >= desugaring.duration);
+
+        let table = stats.to_string();
+        assert_eq!(5, table.lines().count());
+        assert!(table.lines().last().unwrap().starts_with("total"));
+    }
+
+    #[test]
+    fn only_while_measuring() {
+        Lexer::new("a").tokenize_all();
+        let ((), stats) = CompilationStats::measure(|| {
+            let ((), inner) = CompilationStats::measure(|| {
+                Lexer::new("a").tokenize_all();
+            });
+            assert_eq!(1, inner.phases.len());
+        });
+        assert_eq!(CompilationStats::default(), stats);
+    }
+}
@@ -0,0 +1,66 @@
+//! Macros d'assertion pour les tests du langage
+//! Exportées afin que les outils bâtis sur ce crate écrivent des tests concis:
+//!
+//! ```ignore
+//! assert_lexes!("let x = 1;");
+//! assert_lexes!("a <<= b" => [Identifier("a".to_string()), ShlEq, Identifier("b".to_string())]);
+//! assert_errs!("let s = \"abc", code = "E0005");
+//! ```
+//!
+//! Ces macros vérifient le lexer; `assert_parses!` s'ajoutera avec le parseur.
+
+/// Vérifie que le code source est lu sans erreur, et optionnellement les lexèmes produits
+/// Le lexème `EOF` n'est pas inclus.
+#[macro_export]
+macro_rules! assert_lexes {
+    ($source:expr) => {{
+        let source = $source;
+        let (_, errors) = $crate::lexer::Lexer::new(source).tokenize_all();
+        assert!(errors.is_empty(), "{:?} produit des erreurs: {:?}", source, errors);
+    }};
+    ($source:expr => [ $( $token:expr ),* $(,)* ]) => {{
+        let source = $source;
+        let (tokens, errors) = $crate::lexer::Lexer::new(source).tokenize_all();
+        assert!(errors.is_empty(), "{:?} produit des erreurs: {:?}", source, errors);
+        let expected: Vec<$crate::token::TokenType> = vec![ $( $token.into() ),* ];
+        let token_types: Vec<_> = tokens.iter().map(|token| token.token_type().clone()).collect();
+        assert_eq!(expected, token_types, "lexèmes de {:?}", source);
+    }};
+}
+
+/// Vérifie que la lecture du code source produit une erreur portant le code donné
+#[macro_export]
+macro_rules! assert_errs {
+    ($source:expr, code = $code:expr) => {{
+        let source = $source;
+        let (_, errors) = $crate::lexer::Lexer::new(source).tokenize_all();
+        assert!(
+            errors.iter().any(|error| error.code() == $code),
+            "{:?} devait produire l'erreur {}, a produit: {:?}", source, $code, errors
+        );
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use token::Keyword;
+    use token::TokenType::*;
+
+    #[test]
+    fn assertions() {
+        assert_lexes!("let x = 1;");
+        assert_lexes!("a <<= b" => [
+            Identifier("a".to_string()), ShlEq, Identifier("b".to_string()),
+        ]);
+        assert_lexes!("soit" => [Identifier("soit".to_string())]);
+        assert_lexes!("#![keywords(fr)]\nsoit" => [Keyword::Let]);
+        assert_errs!("let s = \"abc", code = "E0005");
+        assert_errs!("a \0 b", code = "E0008");
+    }
+
+    #[test]
+    #[should_panic(expected = "devait produire l'erreur E0007")]
+    fn assert_errs_fails_on_other_error() {
+        assert_errs!("a \0 b", code = "E0007");
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests par instantanés des diagnostics affichés à l'utilisateur
+//!
+//! Chaque fichier `tests/diagnostics/*.nb` est accompagné d'un fichier `.stderr`
+//! contenant le rendu attendu de toutes ses erreurs.
+//! Pour régénérer les instantanés après un changement voulu: `NB_BLESS=1 cargo test`
+
+extern crate nb_parser;
+
+use nb_parser::diagnostic::{Diagnostic, Renderer};
+use nb_parser::lexer::Lexer;
+use nb_parser::locale::Locale;
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Les codes d'erreur du lexer pouvant être produits à partir d'un code source
+const LEXER_CODES: &[&str] = &[
+    "E0002", "E0003", "E0005", "E0006", "E0007", "E0008", "E0009",
+];
+
+fn sources() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("diagnostics");
+    let mut sources: Vec<_> = fs::read_dir(dir)
+        .expect("le dossier tests/diagnostics est introuvable")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "nb"))
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Les diagnostics d'un fichier source, dans l'ordre où les erreurs ont été rencontrées
+fn diagnostics(source: &str) -> Vec<Diagnostic> {
+    let (_, errors) = Lexer::new(source).tokenize_all();
+    errors.iter().map(|err| Diagnostic::from_lexer_error(err, Locale::French)).collect()
+}
+
+#[test]
+fn snapshots() {
+    let bless = env::var_os("NB_BLESS").is_some();
+    let renderer = Renderer::new(false, Locale::French);
+    let mut mismatches = Vec::new();
+
+    for path in sources() {
+        let source = fs::read_to_string(&path).unwrap();
+        let rendered: String = diagnostics(&source).iter()
+            .map(|diagnostic| renderer.render(diagnostic, &source))
+            .collect();
+
+        let snapshot = path.with_extension("stderr");
+        if bless {
+            fs::write(&snapshot, &rendered).unwrap();
+            continue;
+        }
+
+        let expected = fs::read_to_string(&snapshot).unwrap_or_default();
+        if expected != rendered {
+            mismatches.push(format!(
+                "{}\n--- attendu\n{}--- obtenu\n{}",
+                path.display(), expected, rendered
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "instantanés différents:\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn every_lexer_error_is_covered() {
+    let covered: BTreeSet<_> = sources().iter()
+        .flat_map(|path| diagnostics(&fs::read_to_string(path).unwrap()))
+        .filter_map(|diagnostic| diagnostic.code)
+        .collect();
+
+    for code in LEXER_CODES {
+        assert!(covered.contains(code), "aucun instantané ne couvre l'erreur {}", code);
+    }
+}
@@ -0,0 +1,89 @@
+//! Vérifie que l'affichage de l'AST dans un tampon réutilisé n'alloue pas
+#![feature(box_syntax)]
+
+extern crate nb_parser;
+
+use nb_parser::ast::*;
+use nb_parser::token::Keyword;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocateur comptant le nombre d'allocations effectuées
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Une fonction contenant plusieurs centaines d'énoncés
+fn large_function() -> FunctionDeclaration {
+    let int = |name: &str| Variable {
+        name: name.to_string(),
+        category: Type { name: "int".to_string() },
+    };
+    let statements = (0..500)
+        .map(|i| {
+            let call = Expression::FunCall {
+                target: "calculer".to_string(),
+                arguments: vec![
+                    box "a".into(),
+                    box Expression::BinaryExpression(
+                        box "b".into(),
+                        BinaryOperator::Multiplication,
+                        box Literal::from(Number::Int(i)).into(),
+                    ),
+                ],
+            };
+            match i % 3 {
+                0 => Statement::Expression(box call),
+                1 => Statement::Assignment(int("a"), box call),
+                _ => ConditionalStatement {
+                    token: Keyword::Elseif,
+                    condition: Some(box "a".into()),
+                    body: box Block::new(vec![VariableDeclaration {
+                        token: Keyword::Let,
+                        ident: int("c"),
+                        value: box call,
+                    }.into()]),
+                }.into(),
+            }
+        })
+        .collect();
+
+    FunctionDeclaration {
+        identifier: "grande".to_string(),
+        parameters: vec![int("a"), int("b")],
+        body: box Block::new(statements),
+        return_type: Type { name: "int".to_string() },
+    }
+}
+
+#[test]
+fn rendering_into_a_reused_buffer_does_not_allocate() {
+    let function = large_function();
+    let mut buffer = String::new();
+
+    // le premier affichage fait grandir le tampon
+    render_into(&mut buffer, &function);
+    let expected_len = buffer.len();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    render_into(&mut buffer, &function);
+    let allocations = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+    assert_eq!(expected_len, buffer.len());
+    assert_eq!(0, allocations);
+}
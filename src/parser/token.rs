@@ -44,6 +44,23 @@ static KEYWORDS: phf::Map<&'static str, Keyword> = phf_map! {
     "yield" => Reserved(Yield),
 };
 
+/// Synonymes français des mot-clés, actifs avec `KeywordSet::Bilingual`
+/// Ils produisent le même `Keyword` que leur équivalent anglais.
+static FRENCH_KEYWORDS: phf::Map<&'static str, Keyword> = phf_map! {
+    "arreter" => Break,
+    "constante" => Const,
+    "continuer" => Continue,
+    "fonction" => Fun,
+    "retourner" => Return,
+    "sauf" => Unless,
+    "si" => If,
+    "sinon" => Else,
+    "sinonsi" => Elseif,
+    "soit" => Let,
+    "structure" => Struct,
+    "tantque" => While,
+};
+
 /// Représente un lexème dans le programme
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Token {
@@ -87,13 +104,26 @@ pub enum TokenType {
     EOF,
     Underscore, // _
 
-    Eq,       // =
-    Plus,     // +
-    Minus,    // -
-    Division, // /
-    Modulo,   // %
-    Power,    // ^
-    Not,      // !
+    Eq,             // =
+    Plus,           // +
+    Minus,          // -
+    Multiplication, // *
+    Division,       // /
+    Modulo,         // %
+    Power,          // ^
+    Not,            // !
+    Tilde,          // ~
+    Shl,            // <<
+    Shr,            // >>
+
+    PlusEq,           // +=
+    MinusEq,          // -=
+    MultiplicationEq, // *=
+    DivisionEq,       // /=
+    ModuloEq,         // %=
+    PowerEq,          // ^=
+    ShlEq,            // <<=
+    ShrEq,            // >>=
 
     EqEq,  // ==
     NotEq, // !=
@@ -106,6 +136,7 @@ pub enum TokenType {
     And,    // &
     OrOr,   // ||
     AndAnd, // &&
+    Xor,    // ^^
 
     Comma,     // ,
     Colon,     // :
@@ -116,16 +147,142 @@ pub enum TokenType {
     Rbracket,  // ]
     Lbrace,    // {
     Rbrace,    // }
+    Dot,       // .
+    DotDot,    // ..
+    Question,  // ?
 
     Illegal(String),
     Identifier(String), // abcdef
     Comment(String),
+    /// Émis seulement lorsque le lexer est configuré pour émettre les trivia
+    Whitespace(String),
     Keyword(Keyword),
     Boolean(Boolean),
     Literal(String),
     Number(Number),
 }
 
+/// Les catégories de lexèmes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TokenCategory {
+    /// Fin de la séquence
+    EOF,
+    /// Un mot-clé, incluant les mot-clés réservés
+    Keyword,
+    /// Un identifiant
+    Identifier,
+    /// Une valeur littérale: chaîne de caractères, nombre ou booléen
+    Literal,
+    /// Un opérateur arithmétique, de comparaison ou logique
+    Operator,
+    /// Un délimiteur ou séparateur: parenthèses, accolades, virgule, etc.
+    Punctuation,
+    /// Un commentaire ou des espaces-blancs
+    Trivia,
+    /// Un caractère illégal
+    Illegal,
+}
+
+impl TokenType {
+    /// La catégorie du lexème
+    pub fn category(&self) -> TokenCategory {
+        use self::TokenType::*;
+        match *self {
+            EOF => TokenCategory::EOF,
+            Eq | Plus | Minus | Multiplication | Division | Modulo | Power | Not | Tilde | Shl
+            | Shr | PlusEq | MinusEq | MultiplicationEq | DivisionEq | ModuloEq | PowerEq
+            | ShlEq | ShrEq | EqEq | NotEq | Lt | Gt | LtEq | GtEq | Or | And | OrOr | AndAnd
+            | Xor | DotDot | Question => TokenCategory::Operator,
+            Underscore | Comma | Colon | Semicolon | Lparen | Rparen | Lbracket | Rbracket
+            | Lbrace | Rbrace | Dot => TokenCategory::Punctuation,
+            Illegal(_) => TokenCategory::Illegal,
+            Identifier(_) => TokenCategory::Identifier,
+            Comment(_) | Whitespace(_) => TokenCategory::Trivia,
+            Keyword(_) => TokenCategory::Keyword,
+            Boolean(_) | Literal(_) | Number(_) => TokenCategory::Literal,
+        }
+    }
+
+    /// Renvoie si le lexème est un mot-clé
+    pub fn is_keyword(&self) -> bool {
+        self.category() == TokenCategory::Keyword
+    }
+
+    /// Renvoie si le lexème est un opérateur
+    pub fn is_operator(&self) -> bool {
+        self.category() == TokenCategory::Operator
+    }
+
+    /// Renvoie si le lexème est une valeur littérale
+    pub fn is_literal(&self) -> bool {
+        self.category() == TokenCategory::Literal
+    }
+
+    /// Renvoie si le lexème est un trivia, c'est-à-dire un commentaire ou des
+    /// espaces-blancs, qui n'a pas de signification pour le parseur
+    pub fn is_trivia(&self) -> bool {
+        self.category() == TokenCategory::Trivia
+    }
+
+    /// Le texte d'un lexème dont le texte est toujours le même (opérateurs,
+    /// ponctuation, mot-clés et booléens)
+    /// Renvoie `None` pour les lexèmes contenant du texte variable et pour `EOF`.
+    pub fn as_str(&self) -> Option<&'static str> {
+        use self::TokenType::*;
+        let st = match *self {
+            Underscore => "_",
+            Eq => "=",
+            Plus => "+",
+            Minus => "-",
+            Multiplication => "*",
+            Division => "/",
+            Modulo => "%",
+            Power => "^",
+            Not => "!",
+            Tilde => "~",
+            Shl => "<<",
+            Shr => ">>",
+            PlusEq => "+=",
+            MinusEq => "-=",
+            MultiplicationEq => "*=",
+            DivisionEq => "/=",
+            ModuloEq => "%=",
+            PowerEq => "^=",
+            ShlEq => "<<=",
+            ShrEq => ">>=",
+            EqEq => "==",
+            NotEq => "!=",
+            Lt => "<",
+            Gt => ">",
+            LtEq => "<=",
+            GtEq => ">=",
+            Or => "|",
+            And => "&",
+            OrOr => "||",
+            AndAnd => "&&",
+            Xor => "^^",
+            Comma => ",",
+            Colon => ":",
+            Semicolon => ";",
+            Lparen => "(",
+            Rparen => ")",
+            Lbracket => "[",
+            Rbracket => "]",
+            Lbrace => "{",
+            Rbrace => "}",
+            Dot => ".",
+            DotDot => "..",
+            Question => "?",
+            Boolean(self::Boolean::True) => "true",
+            Boolean(self::Boolean::False) => "false",
+            Keyword(keyword) => keyword.as_str(),
+            EOF | Illegal(_) | Identifier(_) | Comment(_) | Whitespace(_) | Literal(_)
+            | Number(_) => return None,
+        };
+        Some(st)
+    }
+}
+
 impl From<Keyword> for TokenType {
     fn from(keyword: Keyword) -> Self {
         TokenType::Keyword(keyword)
@@ -167,6 +324,55 @@ impl Keyword {
     pub(crate) fn lookup(keyword: &str) -> Option<Keyword> {
         KEYWORDS.get(keyword).cloned()
     }
+
+    /// Permet de chercher un mot-clé parmi ceux de l'ensemble passé en paramètre
+    pub(crate) fn lookup_in(keyword: &str, set: KeywordSet) -> Option<Keyword> {
+        match set {
+            KeywordSet::English => Keyword::lookup(keyword),
+            KeywordSet::Bilingual => Keyword::lookup(keyword)
+                .or_else(|| FRENCH_KEYWORDS.get(keyword).cloned()),
+        }
+    }
+
+    /// Le mot-clé tel qu'écrit dans le code source (en anglais)
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Reserved(reserved) => reserved.as_str(),
+            Break => "break",
+            Const => "const",
+            Continue => "continue",
+            Else => "else",
+            Elseif => "elseif",
+            Fun => "fun",
+            If => "if",
+            Let => "let",
+            Return => "return",
+            Struct => "struct",
+            Unless => "unless",
+            While => "while",
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Les mot-clés reconnus par le lexer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeywordSet {
+    /// Seulement les mot-clés anglais
+    English,
+    /// Les mot-clés anglais et leurs synonymes français (`fonction`, `si`, `tantque`, ...)
+    Bilingual,
+}
+
+impl Default for KeywordSet {
+    fn default() -> Self {
+        KeywordSet::English
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -197,20 +403,75 @@ pub enum ReservedKeyword {
     Yield,
 }
 
+impl ReservedKeyword {
+    /// Le mot-clé réservé tel qu'écrit dans le code source
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Alias => "alias",
+            Array => "array",
+            Case => "case",
+            Class => "class",
+            Do => "do",
+            Export => "export",
+            Final => "final",
+            Import => "import",
+            In => "in",
+            Macro => "macro",
+            Of => "of",
+            Override => "override",
+            Private => "private",
+            Protected => "protected",
+            Pub => "pub",
+            Public => "public",
+            Pure => "pure",
+            Static => "static",
+            Switch => "switch",
+            This => "this",
+            Trait => "trait",
+            Use => "use",
+            Virtual => "virtual",
+            Yield => "yield",
+        }
+    }
+}
+
+impl fmt::Display for ReservedKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Boolean {
     True,
     False,
 }
 
+/// Un nombre tel qu'écrit dans le code source, préfixe (`0x`, `0b`, `0o`) et `_` inclus
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Number {
     Binary(String),
     Decimal(String),
+    /// Un nombre à virgule flottante: `3.14`, `1e-9`
+    Float(String),
     Hexadecimal(String),
     Octal(String),
 }
 
+impl Number {
+    /// Le texte original du nombre
+    pub fn as_str(&self) -> &str {
+        use self::Number::*;
+        match *self {
+            Binary(ref st)
+            | Decimal(ref st)
+            | Float(ref st)
+            | Hexadecimal(ref st)
+            | Octal(ref st) => st,
+        }
+    }
+}
+
 /// Représente une position dans un programme
 /// Peut être employé pour attacher de l'information sur un lexème ou autre
 /// IMPORTANT: Position n'est pas relatif à l'entrée, c'est-à-dire
@@ -287,6 +548,10 @@ impl Span {
             Greater => Err(()),
         }
     }
+
+    pub fn begin(&self) -> Position { self.begin }
+
+    pub fn end(&self) -> Position { self.end }
 }
 
 impl fmt::Display for Span {
@@ -302,6 +567,16 @@ pub enum PositionOrSpan {
     Span(Span),
 }
 
+impl PositionOrSpan {
+    /// Convertit en `Span`, une `Position` devient une gamme d'un seul caractère
+    pub fn span(&self) -> Span {
+        match *self {
+            PositionOrSpan::Position(pos) => Span::new(pos, pos),
+            PositionOrSpan::Span(span) => span,
+        }
+    }
+}
+
 impl convert::From<self::Span> for PositionOrSpan {
     fn from(span: Span) -> Self {
         PositionOrSpan::Span(span)
@@ -313,3 +588,39 @@ impl convert::From<self::Position> for PositionOrSpan {
         PositionOrSpan::Position(pos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category() {
+        assert_eq!(TokenCategory::Operator, TokenType::AndAnd.category());
+        assert_eq!(TokenCategory::Punctuation, TokenType::Lbrace.category());
+        assert_eq!(TokenCategory::Literal, TokenType::Boolean(Boolean::True).category());
+        assert!(TokenType::Keyword(Keyword::Reserved(ReservedKeyword::Yield)).is_keyword());
+        assert!(TokenType::Number(Number::Decimal("1".to_string())).is_literal());
+        assert!(TokenType::Comment("/**/".to_string()).is_trivia());
+        assert!(!TokenType::Identifier("x".to_string()).is_operator());
+    }
+
+    #[test]
+    fn as_str() {
+        assert_eq!(Some("<="), TokenType::LtEq.as_str());
+        assert_eq!(Some("elseif"), TokenType::Keyword(Keyword::Elseif).as_str());
+        assert_eq!(Some("pub"), TokenType::Keyword(Keyword::Reserved(ReservedKeyword::Pub)).as_str());
+        assert_eq!(None, TokenType::Identifier("x".to_string()).as_str());
+        assert_eq!(None, TokenType::EOF.as_str());
+    }
+
+    #[test]
+    fn keyword_as_str_round_trips() {
+        for (st, keyword) in KEYWORDS.entries() {
+            assert_eq!(*st, keyword.as_str());
+            assert_eq!(*st, keyword.to_string());
+        }
+        for (_, keyword) in FRENCH_KEYWORDS.entries() {
+            assert_eq!(Some(*keyword), Keyword::lookup(keyword.as_str()));
+        }
+    }
+}
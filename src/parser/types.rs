@@ -0,0 +1,555 @@
+//! Registre des types primitifs
+//! Associe les noms de types utilisés dans les annotations (`let x: int`) à leur
+//! représentation machine, afin que le typechecker et les backends s'entendent.
+//! Les tableaux `[int; 8]` dont la longueur est connue permettent d'omettre la
+//! vérification des bornes pour les indices constants.
+use ast::{Expression, Literal, Number, UnaryOperator, VariableDeclaration};
+
+use locale::Locale;
+use token;
+
+use phf;
+
+use std::fmt;
+use std::str::FromStr;
+
+static PRIMITIVES: phf::Map<&'static str, Primitive> = phf_map! {
+    "bool" => Primitive::Bool,
+    "char" => Primitive::Char,
+    "double" => Primitive::Double,
+    "float" => Primitive::Float,
+    "int" => Primitive::Int,
+    "long" => Primitive::Long,
+    "string" => Primitive::String,
+    "void" => Primitive::Void,
+};
+
+/// Un type primitif du langage
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Primitive {
+    Bool,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    String,
+    Void,
+}
+
+impl Primitive {
+    /// Cherche le type primitif portant ce nom
+    pub fn lookup(name: &str) -> Option<Primitive> {
+        PRIMITIVES.get(name).cloned()
+    }
+
+    /// Le nom du type tel qu'écrit dans le code source
+    pub fn as_str(&self) -> &'static str {
+        use self::Primitive::*;
+        match *self {
+            Bool => "bool",
+            Char => "char",
+            Double => "double",
+            Float => "float",
+            Int => "int",
+            Long => "long",
+            String => "string",
+            Void => "void",
+        }
+    }
+}
+
+impl fmt::Display for Primitive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// La représentation machine d'un type primitif
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Repr {
+    /// Entier signé du nombre de bits indiqué
+    Int(u8),
+    /// Nombre à virgule flottante IEEE 754 du nombre de bits indiqué
+    Float(u8),
+    /// Booléen sur un octet
+    Bool,
+    /// Valeur scalaire unicode sur 32 bits
+    Char,
+    /// Chaîne de caractères UTF-8 allouée
+    Str,
+    /// Aucune valeur
+    Void,
+}
+
+/// Un type tableau: `[int]`, ou `[int; 8]` lorsque sa longueur est connue à la compilation
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ArrayType {
+    pub element: Primitive,
+    pub length: Option<usize>,
+}
+
+impl fmt::Display for ArrayType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.length {
+            Some(length) => write!(f, "[{}; {}]", self.element, length),
+            None => write!(f, "[{}]", self.element),
+        }
+    }
+}
+
+/// Si un accès à un tableau doit être vérifié à l'exécution
+/// L'interpréteur vérifie toujours; seuls les backends peuvent omettre la vérification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BoundsCheck {
+    /// L'indice est une constante dans les bornes du tableau
+    Elided,
+    /// L'indice ou la longueur n'est pas connu à la compilation
+    Required,
+}
+
+/// La largeur du type `int`, configurable par le compilateur (`--int-width=32|64`)
+/// `long` est toujours sur 64 bits
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IntWidth {
+    W32,
+    W64,
+}
+
+impl IntWidth {
+    pub fn bits(&self) -> u8 {
+        match *self {
+            IntWidth::W32 => 32,
+            IntWidth::W64 => 64,
+        }
+    }
+}
+
+impl Default for IntWidth {
+    fn default() -> Self {
+        IntWidth::W32
+    }
+}
+
+impl FromStr for IntWidth {
+    type Err = String;
+
+    fn from_str(st: &str) -> Result<Self, Self::Err> {
+        match st {
+            "32" => Ok(IntWidth::W32),
+            "64" => Ok(IntWidth::W64),
+            _ => Err(format!("Valeur invalide pour --int-width: '{}' (32 ou 64)", st)),
+        }
+    }
+}
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// Le nom de type n'est pas connu, avec le type primitif au nom le plus proche
+    #[fail(display = "Type inconnu: '{}'", 0)]
+    UnknownType(String, Option<Primitive>),
+    /// Un littéral entier ne peut être représenté par le type de sa destination
+    #[fail(display = "Le littéral {} dépasse le type '{}' ({} à {})", literal, target, min, max)]
+    LiteralOutOfRange {
+        literal: String,
+        target: Primitive,
+        min: i64,
+        max: i64,
+        /// Un type pouvant contenir le littéral
+        suggestion: Primitive,
+    },
+    /// Un indice constant hors des bornes d'un tableau de longueur connue
+    #[fail(display = "L'indice {} dépasse le tableau de longueur {}", index, length)]
+    IndexOutOfRange { index: i64, length: usize },
+    /// Le nombre d'éléments d'un tableau littéral diffère de la longueur de son type
+    #[fail(display = "Le tableau contient {} éléments plutôt que {}", found, expected)]
+    ArrayLengthMismatch { expected: usize, found: usize },
+}
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::UnknownType(..) => "E0104",
+            Error::LiteralOutOfRange { .. } => "E0105",
+            Error::IndexOutOfRange { .. } => "E0106",
+            Error::ArrayLengthMismatch { .. } => "E0107",
+        }
+    }
+
+    /// Le type suggéré à la place de celui utilisé, s'il y en a un
+    pub fn suggestion(&self) -> Option<Primitive> {
+        match *self {
+            Error::UnknownType(_, suggestion) => suggestion,
+            Error::LiteralOutOfRange { suggestion, .. } => Some(suggestion),
+            Error::IndexOutOfRange { .. } | Error::ArrayLengthMismatch { .. } => None,
+        }
+    }
+
+    /// Le message de l'erreur dans la langue demandée
+    /// En français, le message est identique à celui de `Display`
+    pub fn message(&self, locale: Locale) -> String {
+        use self::Error::*;
+        match locale {
+            Locale::French => self.to_string(),
+            Locale::English => match *self {
+                UnknownType(ref name, _) => format!("Unknown type: '{}'", name),
+                LiteralOutOfRange { ref literal, target, min, max, .. } => format!(
+                    "The literal {} overflows type '{}' ({} to {})",
+                    literal, target, min, max
+                ),
+                IndexOutOfRange { index, length } => {
+                    format!("Index {} is out of bounds for an array of length {}", index, length)
+                },
+                ArrayLengthMismatch { expected, found } => {
+                    format!("The array has {} elements instead of {}", found, expected)
+                },
+            },
+        }
+    }
+}
+
+/// Le registre des types connus du compilateur
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Registry {
+    /// La largeur de `int`
+    pub int_width: IntWidth,
+}
+
+impl Registry {
+    pub fn new(int_width: IntWidth) -> Self {
+        Registry { int_width }
+    }
+
+    /// Résout un nom de type utilisé dans une annotation
+    pub fn resolve(&self, name: &str) -> Result<Primitive, Error> {
+        Primitive::lookup(name).ok_or_else(|| Error::UnknownType(name.to_owned(), suggest(name)))
+    }
+
+    /// Résout un type tableau, `[int]` ou `[int; 8]`
+    pub fn resolve_array(&self, name: &str) -> Result<ArrayType, Error> {
+        let malformed = || Error::UnknownType(name.to_owned(), None);
+        if !name.starts_with('[') || !name.ends_with(']') {
+            return Err(malformed());
+        }
+        let inner = &name[1..name.len() - 1];
+        let mut parts = inner.splitn(2, ';');
+        let element = self.resolve(parts.next().unwrap_or("").trim())?;
+        let length = match parts.next() {
+            Some(length) => Some(length.trim().parse().map_err(|_| malformed())?),
+            None => None,
+        };
+        Ok(ArrayType { element, length })
+    }
+
+    /// Détermine si l'accès `array[index]` doit être vérifié à l'exécution
+    /// Un indice constant hors des bornes d'un tableau de longueur connue est une erreur,
+    /// incluant un indice négatif comme `-1`.
+    pub fn bounds_check(&self, array: ArrayType, index: &Expression) -> Result<BoundsCheck, Error> {
+        let index = match constant_number(index) {
+            Some((Number::Int(value), _)) => i64::from(value),
+            Some((Number::Long(value), _)) => value,
+            _ => return Ok(BoundsCheck::Required),
+        };
+        match array.length {
+            Some(length) if index < 0 || index as u64 >= length as u64 => {
+                Err(Error::IndexOutOfRange { index, length })
+            },
+            Some(_) => Ok(BoundsCheck::Elided),
+            None => Ok(BoundsCheck::Required),
+        }
+    }
+
+    /// La représentation machine du type primitif
+    pub fn repr(&self, primitive: Primitive) -> Repr {
+        use self::Primitive::*;
+        match primitive {
+            Bool => Repr::Bool,
+            Char => Repr::Char,
+            Double => Repr::Float(64),
+            Float => Repr::Float(32),
+            Int => Repr::Int(self.int_width.bits()),
+            Long => Repr::Int(64),
+            String => Repr::Str,
+            Void => Repr::Void,
+        }
+    }
+
+    /// Les bornes d'un type entier, `None` si le type n'est pas entier
+    pub fn integer_range(&self, primitive: Primitive) -> Option<(i64, i64)> {
+        match self.repr(primitive) {
+            Repr::Int(32) => Some((i64::from(i32::min_value()), i64::from(i32::max_value()))),
+            Repr::Int(_) => Some((i64::min_value(), i64::max_value())),
+            _ => None,
+        }
+    }
+
+    /// Vérifie qu'un littéral numérique peut être représenté par le type `target`
+    /// Seuls les types entiers ont des bornes: un littéral trop grand pour `int`
+    /// devient un `Number::Long`, ce qui n'est une erreur que si `int` est sur 32 bits
+    /// L'erreur affiche `text`, le texte original du littéral (`0xFFFF_FFFF`), s'il est connu.
+    pub fn check_literal(
+        &self,
+        literal: &Number,
+        text: Option<&str>,
+        target: Primitive,
+    ) -> Result<(), Error> {
+        let value = match *literal {
+            Number::Int(value) => i64::from(value),
+            Number::Long(value) => value,
+            Number::Float(_) => return Ok(()),
+        };
+        match self.integer_range(target) {
+            Some((min, max)) if value < min || value > max => Err(Error::LiteralOutOfRange {
+                literal: text.map_or_else(|| value.to_string(), str::to_owned),
+                target,
+                min,
+                max,
+                suggestion: Primitive::Long,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Vérifie un lexème numérique destiné au type `target`, avant d'en construire le littéral
+    /// Un entier ne pouvant être représenté par un `long` n'a pas de littéral et dépasse
+    /// donc tout type entier.
+    pub fn check_token(&self, number: &token::Number, target: Primitive) -> Result<(), Error> {
+        match (Number::parse(number), self.integer_range(target)) {
+            (Some(value), _) => self.check_literal(&value, Some(number.as_str()), target),
+            (None, Some((min, max))) => Err(Error::LiteralOutOfRange {
+                literal: number.as_str().to_owned(),
+                target,
+                min,
+                max,
+                suggestion: Primitive::Double,
+            }),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Vérifie l'annotation d'une déclaration, ainsi que sa valeur si c'est un littéral
+    /// numérique: `let x: int = 3_000_000_000;` et `let x: int = -3_000_000_000;` sont
+    /// des erreurs
+    /// Un tableau littéral doit avoir la longueur de son type, s'il en a une.
+    pub fn check_declaration(&self, decl: &VariableDeclaration) -> Result<(), Error> {
+        let name = &decl.ident.category.name;
+        if name.starts_with('[') {
+            return self.check_array(self.resolve_array(name)?, &decl.value);
+        }
+        let target = self.resolve(name)?;
+        match constant_number(&decl.value) {
+            Some((number, text)) => {
+                self.check_literal(&number, text.as_ref().map(String::as_str), target)
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn check_array(&self, array: ArrayType, value: &Expression) -> Result<(), Error> {
+        let elements = match *value {
+            Expression::Literal(Literal::Array(ref elements)) => elements,
+            _ => return Ok(()),
+        };
+        match array.length {
+            Some(expected) if expected != elements.len() => {
+                return Err(Error::ArrayLengthMismatch { expected, found: elements.len() });
+            },
+            _ => {},
+        }
+        for element in elements {
+            if let Some((number, text)) = constant_number(element) {
+                self.check_literal(&number, text.as_ref().map(String::as_str), array.element)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Le nombre d'un littéral, possiblement précédé de `-`, et son texte original
+/// La négation est calculée ici puisque le parser ne replie pas les constantes.
+fn constant_number(expr: &Expression) -> Option<(Number, Option<String>)> {
+    match *expr {
+        Expression::Literal(Literal::Number(ref number, ref text)) => {
+            Some((number.clone(), text.clone()))
+        },
+        Expression::UnaryExpression(ref operand, UnaryOperator::Negative) => {
+            let (number, text) = constant_number(operand)?;
+            let number = match number {
+                Number::Float(value) => Number::Float(-value),
+                Number::Int(value) => Number::Long(-i64::from(value)),
+                Number::Long(value) => Number::Long(value.checked_neg()?),
+            };
+            Some((number, text.map(|text| format!("-{}", text))))
+        },
+        _ => None,
+    }
+}
+
+/// Le type primitif dont le nom est le plus proche de `name`, à au plus deux modifications
+fn suggest(name: &str) -> Option<Primitive> {
+    let lowercase = name.to_lowercase();
+    PRIMITIVES.entries()
+        .map(|(candidate, primitive)| (distance(&lowercase, candidate), *primitive))
+        .filter(|&(distance, _)| distance <= 2)
+        .min_by_key(|&(distance, primitive)| (distance, primitive.as_str()))
+        .map(|(_, primitive)| primitive)
+}
+
+/// Distance de Levenshtein entre deux chaînes, en caractères
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..b.len() + 1).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let registry = Registry::default();
+        assert_eq!(Ok(Primitive::Int), registry.resolve("int"));
+        assert_eq!(Repr::Int(32), registry.repr(Primitive::Int));
+        assert_eq!(Repr::Int(64), Registry::new(IntWidth::W64).repr(Primitive::Int));
+        assert_eq!(Repr::Int(64), registry.repr(Primitive::Long));
+        assert_eq!(Ok(IntWidth::W64), "64".parse());
+        assert!("16".parse::<IntWidth>().is_err());
+    }
+
+    #[test]
+    fn literal_out_of_range() {
+        let registry = Registry::default();
+        let large = Number::Long(3_000_000_000);
+
+        assert_eq!(Err(Error::LiteralOutOfRange {
+            literal: "3000000000".to_string(),
+            target: Primitive::Int,
+            min: -2_147_483_648,
+            max: 2_147_483_647,
+            suggestion: Primitive::Long,
+        }), registry.check_literal(&large, None, Primitive::Int));
+        assert_eq!(Ok(()), registry.check_literal(&large, None, Primitive::Long));
+        assert_eq!(Ok(()), registry.check_literal(&large, None, Primitive::Double));
+        assert_eq!(Ok(()), Registry::new(IntWidth::W64).check_literal(&large, None, Primitive::Int));
+        assert_eq!(Ok(()), registry.check_literal(&Number::Int(-5), None, Primitive::Int));
+
+        let declaration = |ty: &str| VariableDeclaration {
+            token: ::token::Keyword::Let,
+            ident: ::ast::Variable {
+                name: "x".to_string(),
+                category: ::ast::Type { name: ty.to_string() },
+            },
+            value: box Literal::from(large.clone()).into(),
+        };
+        assert!(registry.check_declaration(&declaration("int")).is_err());
+        assert_eq!(Ok(()), registry.check_declaration(&declaration("long")));
+
+        // le littéral est affiché tel qu'écrit
+        let hex = ::token::Number::Hexadecimal("0xFFFF_FFFF".to_string());
+        let decl = VariableDeclaration {
+            value: box Literal::from_token(&hex).unwrap().into(),
+            ..declaration("int")
+        };
+        assert_eq!(
+            "Le littéral 0xFFFF_FFFF dépasse le type 'int' (-2147483648 à 2147483647)",
+            registry.check_declaration(&decl).unwrap_err().to_string()
+        );
+
+        // la négation d'un littéral est vérifiée
+        let negative = |number: Number, text: &str| VariableDeclaration {
+            value: box Expression::UnaryExpression(
+                box Literal::Number(number, Some(text.to_string())).into(),
+                UnaryOperator::Negative,
+            ),
+            ..declaration("int")
+        };
+        assert_eq!(
+            "Le littéral -3_000_000_000 dépasse le type 'int' (-2147483648 à 2147483647)",
+            registry
+                .check_declaration(&negative(large.clone(), "3_000_000_000"))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(Ok(()), registry.check_declaration(&negative(Number::Int(300), "300")));
+
+        // un entier ne pouvant être représenté par un `long` dépasse tout type entier
+        let huge = ::token::Number::Decimal("99999999999999999999".to_string());
+        assert_eq!(None, Literal::from_token(&huge));
+        let err = registry.check_token(&huge, Primitive::Long).unwrap_err();
+        assert_eq!("E0105", err.code());
+        assert_eq!(Some(Primitive::Double), err.suggestion());
+        assert_eq!(Ok(()), registry.check_token(&huge, Primitive::Double));
+        assert!(registry.check_token(&hex, Primitive::Int).is_err());
+        assert_eq!(Ok(()), registry.check_token(&hex, Primitive::Long));
+    }
+
+    #[test]
+    fn arrays() {
+        let registry = Registry::default();
+        let bytes = ArrayType { element: Primitive::Int, length: Some(8) };
+        assert_eq!(Ok(bytes), registry.resolve_array("[int; 8]"));
+        assert_eq!("[int; 8]", bytes.to_string());
+        assert_eq!(
+            Ok(ArrayType { element: Primitive::String, length: None }),
+            registry.resolve_array("[string]")
+        );
+        assert!(registry.resolve_array("[int; huit]").is_err());
+        assert!(registry.resolve_array("[nombre; 8]").unwrap_err().code() == "E0104");
+
+        let index = |value: i32| Expression::from(Literal::from(Number::Int(value)));
+        assert_eq!(Ok(BoundsCheck::Elided), registry.bounds_check(bytes, &index(7)));
+        assert_eq!(
+            Err(Error::IndexOutOfRange { index: 8, length: 8 }),
+            registry.bounds_check(bytes, &index(8))
+        );
+        // `-1` est lu comme la négation du littéral `1`
+        let negative = Expression::UnaryExpression(box index(1), UnaryOperator::Negative);
+        assert_eq!(
+            Err(Error::IndexOutOfRange { index: -1, length: 8 }),
+            registry.bounds_check(bytes, &negative)
+        );
+        let not = Expression::UnaryExpression(box index(1), UnaryOperator::BitNot);
+        assert_eq!(Ok(BoundsCheck::Required), registry.bounds_check(bytes, &not));
+        let unknown = ArrayType { length: None, ..bytes };
+        assert_eq!(Ok(BoundsCheck::Required), registry.bounds_check(unknown, &index(8)));
+        let variable = Expression::Identifier("i".to_string());
+        assert_eq!(Ok(BoundsCheck::Required), registry.bounds_check(bytes, &variable));
+
+        let declaration = |ty: &str, length: usize| VariableDeclaration {
+            token: ::token::Keyword::Let,
+            ident: ::ast::Variable {
+                name: "a".to_string(),
+                category: ::ast::Type { name: ty.to_string() },
+            },
+            value: box Literal::Array((0..length).map(|_| box index(1)).collect()).into(),
+        };
+        assert_eq!(Ok(()), registry.check_declaration(&declaration("[int; 2]", 2)));
+        assert_eq!(Ok(()), registry.check_declaration(&declaration("[int]", 3)));
+        assert_eq!(
+            Err(Error::ArrayLengthMismatch { expected: 2, found: 3 }),
+            registry.check_declaration(&declaration("[int; 2]", 3))
+        );
+    }
+
+    #[test]
+    fn unknown_type_suggestions() {
+        let registry = Registry::default();
+        assert_eq!(
+            Err(Error::UnknownType("Int".to_string(), Some(Primitive::Int))),
+            registry.resolve("Int")
+        );
+        assert_eq!(Some(Primitive::String), registry.resolve("strng").unwrap_err().suggestion());
+        assert_eq!(Some(Primitive::Bool), registry.resolve("boool").unwrap_err().suggestion());
+        assert_eq!(None, registry.resolve("matrice").unwrap_err().suggestion());
+    }
+}
@@ -0,0 +1,230 @@
+//! Vérification des invariants structurels de l'AST
+//! Un AST produit par le parseur, une réécriture ou un outil doit respecter ces invariants;
+//! une violation indique un bogue chez son producteur et non une erreur de l'utilisateur.
+//! Les réécritures de ce crate vérifient leur résultat avec `debug_assert_valid`.
+use ast::*;
+use token::{Keyword, Span};
+use visit::{self, Visitor};
+
+/// Un invariant de l'AST qui n'est pas respecté
+#[derive(Clone, Debug, Eq, Fail, PartialEq)]
+pub enum Violation {
+    /// Un identifiant vide, avec le genre de noeud qui le contient
+    #[fail(display = "Identifiant vide dans {}", 0)]
+    EmptyIdentifier(&'static str),
+    /// Un `if`, `unless`, `elseif` ou `while` sans condition
+    #[fail(display = "'{}' sans condition", 0)]
+    MissingCondition(Keyword),
+    /// Un `else` avec une condition
+    #[fail(display = "'else' avec une condition")]
+    ElseWithCondition,
+    /// Un `elseif` ou un `else` qui ne suit pas un `if`, `unless` ou `elseif`
+    #[fail(display = "'{}' ne suit pas une clause", 0)]
+    DetachedClause(Keyword),
+    /// Un mot-clé qui ne peut introduire ce genre de noeud
+    #[fail(display = "Mot-clé '{}' invalide pour {}", 0, 1)]
+    InvalidKeyword(Keyword, &'static str),
+    /// Une gamme dont la fin précède le début
+    #[fail(display = "Gamme inversée: {}", 0)]
+    UnorderedSpan(Span),
+}
+
+/// Les invariants de la fonction qui ne sont pas respectés, dans l'ordre du parcours
+pub fn validate(function: &FunctionDeclaration) -> Vec<Violation> {
+    let mut validator = Validator::default();
+    if function.identifier.is_empty() {
+        validator.violations.push(Violation::EmptyIdentifier("une déclaration de fonction"));
+    }
+    validator.check_type(&function.return_type, "le type de retour");
+    for param in &function.parameters {
+        validator.check_type(&param.category, "le type d'un paramètre");
+    }
+    validator.visit_function(function);
+    validator.violations
+}
+
+/// Panique si la fonction ne respecte pas les invariants, seulement en mode debug
+pub fn debug_assert_valid(function: &FunctionDeclaration) {
+    if cfg!(debug_assertions) {
+        let violations = validate(function);
+        assert!(
+            violations.is_empty(),
+            "AST invalide pour '{}': {:?}", function.identifier, violations
+        );
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn check_type(&mut self, ty: &Type, node: &'static str) {
+        if ty.name.is_empty() {
+            self.violations.push(Violation::EmptyIdentifier(node));
+        }
+    }
+
+    fn check_span(&mut self, span: Span) {
+        if span.end() < span.begin() {
+            self.violations.push(Violation::UnorderedSpan(span));
+        }
+    }
+}
+
+impl Visitor for Validator {
+    fn visit_block(&mut self, block: &Block) {
+        // si l'énoncé précédent est une clause pouvant être suivie d'un `elseif` ou `else`
+        let mut after_clause = false;
+        for stmt in block.statements() {
+            after_clause = match *stmt {
+                Statement::Conditional(ref cond) => match cond.token {
+                    Keyword::Elseif | Keyword::Else if !after_clause => {
+                        self.violations.push(Violation::DetachedClause(cond.token));
+                        cond.token == Keyword::Elseif
+                    },
+                    Keyword::Else => false,
+                    _ => true,
+                },
+                _ => false,
+            };
+        }
+        visit::walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match *stmt {
+            Statement::Conditional(ref cond) => match (cond.token, &cond.condition) {
+                (Keyword::If, &None) | (Keyword::Unless, &None) | (Keyword::Elseif, &None) => {
+                    self.violations.push(Violation::MissingCondition(cond.token));
+                },
+                (Keyword::Else, &Some(_)) => self.violations.push(Violation::ElseWithCondition),
+                (Keyword::If, _) | (Keyword::Unless, _) | (Keyword::Elseif, _)
+                | (Keyword::Else, _) => {},
+                (token, _) => self.violations.push(Violation::InvalidKeyword(token, "une clause")),
+            },
+            Statement::Loop(ref looping) => match (looping.token, &looping.condition) {
+                (Keyword::While, &None) => {
+                    self.violations.push(Violation::MissingCondition(Keyword::While));
+                },
+                (Keyword::While, _) => {},
+                (token, _) => self.violations.push(Violation::InvalidKeyword(token, "une boucle")),
+            },
+            Statement::VariableDeclaration(ref decl) | Statement::LetElse(ref decl, _) => {
+                match decl.token {
+                    Keyword::Let | Keyword::Const => {},
+                    token => {
+                        self.violations.push(Violation::InvalidKeyword(token, "une déclaration"));
+                    },
+                }
+                self.check_type(&decl.ident.category, "le type d'une déclaration");
+            },
+            Statement::Error(span) => self.check_span(span),
+            Statement::Assignment(..) | Statement::Expression(_) | Statement::Return(_) => {},
+        }
+        visit::walk_statement(self, stmt);
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        match *expr {
+            Expression::Identifier(ref name) if name.is_empty() => {
+                self.violations.push(Violation::EmptyIdentifier("une expression"));
+            },
+            Expression::FunCall { ref target, .. } if target.is_empty() => {
+                self.violations.push(Violation::EmptyIdentifier("un appel de fonction"));
+            },
+            Expression::Error(span) => self.check_span(span),
+            _ => {},
+        }
+        visit::walk_expression(self, expr);
+    }
+
+    fn visit_variable(&mut self, var: &Variable) {
+        if var.name.is_empty() {
+            self.violations.push(Violation::EmptyIdentifier("une variable"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::Position;
+
+    fn function(body: Vec<Statement>) -> FunctionDeclaration {
+        FunctionDeclaration {
+            identifier: "f".to_string(),
+            parameters: vec![],
+            body: box Block::new(body),
+            return_type: Type { name: "void".to_string() },
+        }
+    }
+
+    fn clause(token: Keyword, condition: Option<&str>) -> Statement {
+        ConditionalStatement {
+            token,
+            condition: condition.map(|cond| box cond.into()),
+            body: box Block::new(vec![]),
+        }.into()
+    }
+
+    #[test]
+    fn valid_function() {
+        let fun = function(vec![
+            clause(Keyword::If, Some("a")),
+            clause(Keyword::Elseif, Some("b")),
+            clause(Keyword::Else, None),
+            Statement::Return(Some(box "a".into())),
+        ]);
+        assert_eq!(Vec::<Violation>::new(), validate(&fun));
+        debug_assert_valid(&fun);
+    }
+
+    #[test]
+    fn clauses() {
+        let fun = function(vec![
+            clause(Keyword::Else, None),
+            clause(Keyword::If, None),
+            clause(Keyword::Else, Some("c")),
+            clause(Keyword::Elseif, Some("d")),
+            clause(Keyword::Return, Some("e")),
+        ]);
+        assert_eq!(vec![
+            Violation::DetachedClause(Keyword::Else),
+            Violation::DetachedClause(Keyword::Elseif),
+            Violation::MissingCondition(Keyword::If),
+            Violation::ElseWithCondition,
+            Violation::InvalidKeyword(Keyword::Return, "une clause"),
+        ], validate(&fun));
+    }
+
+    #[test]
+    fn identifiers_and_spans() {
+        let reversed = Span::new(Position::new(2, 1), Position::new(1, 1));
+        let mut fun = function(vec![
+            Statement::Expression(box Expression::FunCall {
+                target: String::new(),
+                arguments: vec![box Expression::Identifier(String::new())],
+            }),
+            Statement::Error(reversed),
+        ]);
+        fun.identifier = String::new();
+
+        let violations = validate(&fun);
+        assert_eq!(vec![
+            Violation::EmptyIdentifier("une déclaration de fonction"),
+            Violation::EmptyIdentifier("un appel de fonction"),
+            Violation::EmptyIdentifier("une expression"),
+            Violation::UnorderedSpan(reversed),
+        ], violations);
+        assert_eq!("Identifiant vide dans une expression", violations[2].to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "AST invalide pour 'f'")]
+    #[cfg(debug_assertions)]
+    fn debug_assertion() {
+        debug_assert_valid(&function(vec![clause(Keyword::Else, None)]));
+    }
+}
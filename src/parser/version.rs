@@ -0,0 +1,182 @@
+//! Versions du langage et fonctionnalités qu'elles introduisent
+//! Un fichier écrit pour une version antérieure reçoit un diagnostic nommant la version
+//! requise plutôt qu'une erreur de syntaxe, ce qui permet à la grammaire d'évoluer sans
+//! briser le matériel de cours existant.
+use locale::Locale;
+use token::Span;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Une version du langage, de la forme `majeure.mineure`
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LanguageVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl LanguageVersion {
+    pub const V0_1: LanguageVersion = LanguageVersion { major: 0, minor: 1 };
+    pub const V0_2: LanguageVersion = LanguageVersion { major: 0, minor: 2 };
+    /// La version la plus récente, utilisée lorsqu'aucune n'est demandée
+    pub const LATEST: LanguageVersion = LanguageVersion::V0_2;
+    /// Toutes les versions publiées, de la plus ancienne à la plus récente
+    pub const ALL: &'static [LanguageVersion] = &[LanguageVersion::V0_1, LanguageVersion::V0_2];
+
+    /// Si la fonctionnalité est disponible dans cette version
+    pub fn supports(&self, feature: Feature) -> bool {
+        *self >= feature.required_version()
+    }
+
+    /// Vérifie que la fonctionnalité, utilisée à `span`, est disponible dans cette version
+    pub fn require(&self, feature: Feature, span: Span) -> Result<(), Error> {
+        if self.supports(feature) {
+            Ok(())
+        }
+        else {
+            Err(Error::Unavailable(feature, feature.required_version(), *self, span))
+        }
+    }
+}
+
+impl Default for LanguageVersion {
+    fn default() -> Self {
+        LanguageVersion::LATEST
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for LanguageVersion {
+    type Err = String;
+
+    /// Lit une version connue, comme `0.1`
+    fn from_str(st: &str) -> Result<Self, Self::Err> {
+        let version = match st.find('.') {
+            Some(idx) => match (st[..idx].parse(), st[idx + 1..].parse()) {
+                (Ok(major), Ok(minor)) => Some(LanguageVersion { major, minor }),
+                _ => None,
+            },
+            None => None,
+        };
+        match version {
+            Some(version) if LanguageVersion::ALL.contains(&version) => Ok(version),
+            _ => Err(format!(
+                "Version du langage invalide: '{}' (la plus récente est {})",
+                st, LanguageVersion::LATEST
+            )),
+        }
+    }
+}
+
+/// Une fonctionnalité du langage absente de la première version
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Feature {
+    /// Les fonctions anonymes
+    Lambda,
+    /// L'énoncé `switch`
+    Switch,
+    /// L'interpolation dans les chaînes de caractères
+    Interpolation,
+}
+
+impl Feature {
+    /// La première version du langage offrant la fonctionnalité
+    pub fn required_version(&self) -> LanguageVersion {
+        match *self {
+            Feature::Lambda | Feature::Switch | Feature::Interpolation => LanguageVersion::V0_2,
+        }
+    }
+
+    fn name(&self, locale: Locale) -> &'static str {
+        match (*self, locale) {
+            (Feature::Lambda, Locale::French) => "les fonctions anonymes",
+            (Feature::Lambda, Locale::English) => "lambdas",
+            (Feature::Switch, _) => "switch",
+            (Feature::Interpolation, Locale::French) => "l'interpolation de chaînes",
+            (Feature::Interpolation, Locale::English) => "string interpolation",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name(Locale::French))
+    }
+}
+
+#[derive(Debug, Eq, Fail, PartialEq)]
+pub enum Error {
+    /// La fonctionnalité, la version qu'elle requiert et la version demandée
+    #[fail(display = "{} requiert la version {} du langage ({} demandée)", 0, 1, 2)]
+    Unavailable(Feature, LanguageVersion, LanguageVersion, Span),
+}
+
+impl Error {
+    /// Le code de l'erreur, qui ne dépend pas de la langue des messages
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Error::Unavailable(..) => "E0110",
+        }
+    }
+
+    /// La gamme où la fonctionnalité est utilisée
+    pub fn span(&self) -> Span {
+        match *self {
+            Error::Unavailable(_, _, _, span) => span,
+        }
+    }
+
+    /// Le message de l'erreur dans la langue demandée
+    /// En français, le message est identique à celui de `Display`
+    pub fn message(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (_, Locale::French) => self.to_string(),
+            (&Error::Unavailable(feature, required, used, _), Locale::English) => format!(
+                "{} requires language version {} ({} requested)",
+                feature.name(locale), required, used
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token::Position;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(Ok(LanguageVersion::V0_1), "0.1".parse());
+        assert_eq!("0.2", LanguageVersion::V0_2.to_string());
+        assert_eq!(LanguageVersion::LATEST, LanguageVersion::default());
+        assert!("0".parse::<LanguageVersion>().is_err());
+        assert!("0.x".parse::<LanguageVersion>().is_err());
+        assert!("9.0".parse::<LanguageVersion>().is_err());
+        assert!("0.0".parse::<LanguageVersion>().is_err());
+        assert_eq!(Some(&LanguageVersion::LATEST), LanguageVersion::ALL.last());
+    }
+
+    #[test]
+    fn gating() {
+        let span = Span::new(Position::new(3, 1), Position::new(3, 6));
+        assert!(LanguageVersion::V0_2.supports(Feature::Switch));
+        assert_eq!(Ok(()), LanguageVersion::LATEST.require(Feature::Lambda, span));
+
+        let err = LanguageVersion::V0_1.require(Feature::Switch, span).unwrap_err();
+        assert_eq!("E0110", err.code());
+        assert_eq!(span, err.span());
+        assert_eq!(
+            "switch requiert la version 0.2 du langage (0.1 demandée)",
+            err.message(Locale::French)
+        );
+        assert_eq!(
+            "switch requires language version 0.2 (0.1 requested)",
+            err.message(Locale::English)
+        );
+    }
+}
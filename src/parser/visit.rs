@@ -0,0 +1,102 @@
+//! Parcours générique de l'AST.
+//! Chaque méthode de `Visitor` a une implémentation par défaut qui visite les
+//! enfants du noeud à l'aide de la fonction `walk_*` correspondante.
+//! Une implémentation peut donc redéfinir uniquement les noeuds qui l'intéressent
+//! et appeler la fonction `walk_*` pour continuer le parcours.
+use ast::*;
+
+pub trait Visitor {
+    fn visit_function(&mut self, function: &FunctionDeclaration) {
+        walk_function(self, function)
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block)
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        walk_statement(self, stmt)
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        walk_expression(self, expr)
+    }
+
+    fn visit_variable(&mut self, _var: &Variable) {}
+}
+
+pub fn walk_function<V: Visitor + ?Sized>(visitor: &mut V, function: &FunctionDeclaration) {
+    for param in &function.parameters {
+        visitor.visit_variable(param);
+    }
+    visitor.visit_block(&function.body);
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &Block) {
+    for stmt in block.statements() {
+        visitor.visit_statement(stmt);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Statement) {
+    use ast::Statement::*;
+    match *stmt {
+        Assignment(ref var, ref expr) => {
+            visitor.visit_variable(var);
+            visitor.visit_expression(expr);
+        },
+        Conditional(ref cond) => {
+            if let Some(ref condition) = cond.condition {
+                visitor.visit_expression(condition);
+            }
+            visitor.visit_block(&cond.body);
+        },
+        Loop(ref looping) => {
+            if let Some(ref condition) = looping.condition {
+                visitor.visit_expression(condition);
+            }
+            visitor.visit_block(&looping.body);
+        },
+        Expression(ref expr) => visitor.visit_expression(expr),
+        Return(ref expr) => if let Some(ref expr) = *expr {
+            visitor.visit_expression(expr);
+        },
+        VariableDeclaration(ref decl) => {
+            // la valeur est visitée avant la déclaration: `let x = x;` réfère
+            // à la variable précédente
+            visitor.visit_expression(&decl.value);
+            visitor.visit_variable(&decl.ident);
+        },
+        LetElse(ref decl, ref otherwise) => {
+            // la variable n'existe pas dans le bloc `else`
+            visitor.visit_expression(&decl.value);
+            visitor.visit_block(otherwise);
+            visitor.visit_variable(&decl.ident);
+        },
+        // un noeud d'erreur n'a pas d'enfants
+        Error(_) => {},
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression) {
+    use ast::Expression::*;
+    match *expr {
+        Identifier(_) => {},
+        Literal(ref lit) => if let ::ast::Literal::Array(ref elements) = *lit {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        },
+        FunCall { ref arguments, .. } => {
+            for arg in arguments {
+                visitor.visit_expression(arg);
+            }
+        },
+        BinaryExpression(ref lhs, _, ref rhs) => {
+            visitor.visit_expression(lhs);
+            visitor.visit_expression(rhs);
+        },
+        UnaryExpression(ref expr, _) => visitor.visit_expression(expr),
+        Error(_) => {},
+    }
+}
@@ -0,0 +1,158 @@
+//! Capacités requises par les fonctions de la librairie standard.
+//! Chaque fonction ayant un effet hors du programme est liée à une capacité. Un programme
+//! hôte choisit une `Policy` avant d'exécuter du code non fiable: un appel à une fonction
+//! dont la capacité est refusée est rejeté par `Policy::check`, avant l'exécution.
+use io::{self, Io};
+use string::{self, Signature};
+
+use std::fmt;
+
+/// Une capacité pouvant être accordée ou refusée à un programme
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Capability {
+    /// Lire et écrire des fichiers
+    Filesystem,
+    /// Lire l'entrée standard
+    Stdin,
+    /// Accéder au réseau
+    Net,
+    /// Lire l'heure courante
+    Time,
+    /// Générer des nombres aléatoires
+    Random,
+}
+
+impl Capability {
+    /// Toutes les capacités
+    pub const ALL: &'static [Capability] = &[
+        Capability::Filesystem,
+        Capability::Stdin,
+        Capability::Net,
+        Capability::Time,
+        Capability::Random,
+    ];
+
+    fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Capability::Filesystem => "fs",
+            Capability::Stdin => "stdin",
+            Capability::Net => "net",
+            Capability::Time => "time",
+            Capability::Random => "random",
+        })
+    }
+}
+
+/// Un appel à une fonction dont la capacité est refusée
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Denied {
+    /// Le nom de la fonction appelée
+    pub function: &'static str,
+    pub capability: Capability,
+}
+
+impl fmt::Display for Denied {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "La fonction '{}' requiert la capacité '{}', qui est refusée",
+               self.function, self.capability)
+    }
+}
+
+/// L'ensemble des capacités accordées à un programme
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Policy {
+    allowed: u8,
+}
+
+impl Policy {
+    /// Toutes les capacités sont accordées
+    pub fn allow_all() -> Self {
+        Capability::ALL.iter().fold(Policy::deny_all(), |policy, &cap| policy.allow(cap))
+    }
+
+    /// Aucune capacité n'est accordée
+    pub fn deny_all() -> Self {
+        Policy { allowed: 0 }
+    }
+
+    /// Accorde la capacité
+    pub fn allow(mut self, capability: Capability) -> Self {
+        self.allowed |= capability.bit();
+        self
+    }
+
+    /// Refuse la capacité
+    pub fn deny(mut self, capability: Capability) -> Self {
+        self.allowed &= !capability.bit();
+        self
+    }
+
+    /// Si la capacité est accordée
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.allowed & capability.bit() != 0
+    }
+
+    /// Vérifie qu'un appel à la fonction est permis
+    pub fn check(&self, signature: &Signature) -> Result<(), Denied> {
+        match signature.capability {
+            Some(capability) if !self.allows(capability) => {
+                Err(Denied { function: signature.name, capability })
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Les entrées/sorties permises par cette politique
+    pub fn io(&self) -> Io {
+        Io {
+            filesystem: self.allows(Capability::Filesystem),
+            stdin: self.allows(Capability::Stdin),
+        }
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::allow_all()
+    }
+}
+
+/// Cherche la signature d'une fonction de la librairie standard
+pub fn signature(name: &str) -> Option<&'static Signature> {
+    string::SIGNATURES.iter()
+        .chain(io::SIGNATURES.iter())
+        .find(|signature| signature.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy() {
+        let policy = Policy::deny_all().allow(Capability::Stdin).allow(Capability::Time);
+
+        assert!(policy.allows(Capability::Stdin));
+        assert!(!policy.allows(Capability::Filesystem));
+        assert!(!policy.deny(Capability::Time).allows(Capability::Time));
+        assert_eq!(Io { filesystem: false, stdin: true }, policy.io());
+        assert_eq!(Io::allow_all(), Policy::default().io());
+    }
+
+    #[test]
+    fn check() {
+        let policy = Policy::allow_all().deny(Capability::Filesystem);
+
+        assert_eq!(Ok(()), policy.check(signature("len").unwrap()));
+        assert_eq!(Ok(()), policy.check(signature("read_line").unwrap()));
+        assert_eq!(Err(Denied { function: "write_file", capability: Capability::Filesystem }),
+                   policy.check(signature("write_file").unwrap()));
+        assert_eq!(None, signature("inexistante"));
+    }
+}
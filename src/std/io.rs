@@ -0,0 +1,148 @@
+//! Fonctions d'entrée/sortie de la librairie standard.
+//! L'accès au système de fichiers et à l'entrée standard est contrôlé par `Io`,
+//! permettant à un programme hôte de les désactiver pour exécuter du code non fiable.
+use capability::Capability;
+use string::Signature;
+
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead};
+
+/// Les signatures des fonctions de ce module
+pub static SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "read_file",
+        parameters: &["string"],
+        return_type: "string",
+        capability: Some(Capability::Filesystem),
+    },
+    Signature {
+        name: "write_file",
+        parameters: &["string", "string"],
+        return_type: "void",
+        capability: Some(Capability::Filesystem),
+    },
+    Signature {
+        name: "read_line",
+        parameters: &[],
+        return_type: "string",
+        capability: Some(Capability::Stdin),
+    },
+];
+
+/// Erreur renvoyée par les fonctions de ce module
+#[derive(Debug)]
+pub enum Error {
+    /// L'accès au système de fichiers est désactivé
+    FilesystemDenied,
+    /// L'accès à l'entrée standard est désactivé
+    StdinDenied,
+    Io(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::FilesystemDenied => write!(f, "L'accès au système de fichiers est désactivé"),
+            Error::StdinDenied => write!(f, "L'accès à l'entrée standard est désactivé"),
+            Error::Io(ref err) => write!(f, "Erreur d'entrée/sortie: {}", err),
+        }
+    }
+}
+
+/// Donne accès aux fonctions d'entrée/sortie selon les permissions accordées
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Io {
+    /// Permet `read_file` et `write_file`
+    pub filesystem: bool,
+    /// Permet `read_line`
+    pub stdin: bool,
+}
+
+impl Io {
+    /// Toutes les permissions sont accordées
+    pub fn allow_all() -> Self {
+        Io { filesystem: true, stdin: true }
+    }
+
+    /// Aucune permission n'est accordée
+    pub fn deny_all() -> Self {
+        Io { filesystem: false, stdin: false }
+    }
+
+    /// Lit le contenu complet d'un fichier
+    pub fn read_file(&self, path: &str) -> Result<String, Error> {
+        if !self.filesystem {
+            return Err(Error::FilesystemDenied);
+        }
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Écrit `contents` dans un fichier, le remplaçant s'il existe
+    pub fn write_file(&self, path: &str, contents: &str) -> Result<(), Error> {
+        if !self.filesystem {
+            return Err(Error::FilesystemDenied);
+        }
+        Ok(fs::write(path, contents)?)
+    }
+
+    /// Lit une ligne de l'entrée standard, sans la fin de ligne
+    pub fn read_line(&self) -> Result<String, Error> {
+        if !self.stdin {
+            return Err(Error::StdinDenied);
+        }
+        let stdin = io::stdin();
+        let mut line = String::new();
+        stdin.lock().read_line(&mut line)?;
+        let len = line.trim_right_matches(|ch| ch == '\n' || ch == '\r').len();
+        line.truncate(len);
+        Ok(line)
+    }
+}
+
+impl Default for Io {
+    fn default() -> Self {
+        Io::allow_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn write_then_read() {
+        let path = env::temp_dir().join("nb_std_io_write_then_read.txt");
+        let path = path.to_str().unwrap();
+        let io = Io::allow_all();
+
+        io.write_file(path, "bonjour\n").unwrap();
+        assert_eq!("bonjour\n", io.read_file(path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn denied() {
+        let io = Io::deny_all();
+
+        match io.read_file("inexistant") {
+            Err(Error::FilesystemDenied) => {},
+            other => panic!("Résultat inattendu: {:?}", other),
+        }
+        match io.write_file("inexistant", "") {
+            Err(Error::FilesystemDenied) => {},
+            other => panic!("Résultat inattendu: {:?}", other),
+        }
+        match io.read_line() {
+            Err(Error::StdinDenied) => {},
+            other => panic!("Résultat inattendu: {:?}", other),
+        }
+    }
+}
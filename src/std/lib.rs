@@ -1,3 +1,7 @@
+pub mod capability;
+pub mod io;
+pub mod string;
+
 #[cfg(test)]
 mod tests {
     #[test]
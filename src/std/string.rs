@@ -0,0 +1,156 @@
+//! Fonctions de la librairie standard opérant sur les chaînes de caractères.
+//! Les positions et longueurs sont exprimées en caractères et non en bytes.
+use capability::Capability;
+
+use std::fmt;
+
+/// Signature d'une fonction de la librairie standard, telle que vue par le
+/// vérificateur de types
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Signature {
+    /// Le nom de la fonction dans le langage
+    pub name: &'static str,
+    /// Le nom du type de chaque paramètre
+    pub parameters: &'static [&'static str],
+    /// Le nom du type de retour
+    pub return_type: &'static str,
+    /// La capacité requise pour appeler la fonction, `None` si elle n'a pas d'effet
+    pub capability: Option<Capability>,
+}
+
+/// Les signatures des fonctions de ce module
+pub static SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "len",
+        parameters: &["string"],
+        return_type: "int",
+        capability: None,
+    },
+    Signature {
+        name: "substring",
+        parameters: &["string", "int", "int"],
+        return_type: "string",
+        capability: None,
+    },
+    Signature {
+        name: "split",
+        parameters: &["string", "string"],
+        return_type: "[string]",
+        capability: None,
+    },
+    Signature {
+        name: "contains",
+        parameters: &["string", "string"],
+        return_type: "bool",
+        capability: None,
+    },
+    Signature {
+        name: "to_upper",
+        parameters: &["string"],
+        return_type: "string",
+        capability: None,
+    },
+    Signature {
+        name: "parse_int",
+        parameters: &["string"],
+        return_type: "int",
+        capability: None,
+    },
+];
+
+/// Erreur renvoyée par les fonctions de ce module
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// La gamme demandée dépasse la chaîne de caractères
+    OutOfBounds { begin: usize, end: usize, len: usize },
+    /// La chaîne de caractères n'est pas un entier valide
+    InvalidInt(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::OutOfBounds { begin, end, len } => write!(
+                f, "La gamme {}..{} dépasse la chaîne de longueur {}", begin, end, len
+            ),
+            Error::InvalidInt(ref st) => write!(f, "Entier invalide: '{}'", st),
+        }
+    }
+}
+
+/// Le nombre de caractères dans la chaîne
+pub fn len(st: &str) -> usize {
+    st.chars().count()
+}
+
+/// Les caractères de `begin` (inclusif) à `end` (exclusif)
+pub fn substring(st: &str, begin: usize, end: usize) -> Result<String, Error> {
+    let len = len(st);
+    if begin > end || end > len {
+        return Err(Error::OutOfBounds { begin, end, len });
+    }
+    Ok(st.chars().skip(begin).take(end - begin).collect())
+}
+
+/// Sépare la chaîne à chaque occurrence du séparateur
+/// Un séparateur vide sépare chaque caractère
+pub fn split(st: &str, separator: &str) -> Vec<String> {
+    if separator.is_empty() {
+        st.chars().map(|ch| ch.to_string()).collect()
+    }
+    else {
+        st.split(separator).map(str::to_owned).collect()
+    }
+}
+
+/// Renvoie si la chaîne contient `needle`
+pub fn contains(st: &str, needle: &str) -> bool {
+    st.contains(needle)
+}
+
+/// Convertit la chaîne en majuscules
+pub fn to_upper(st: &str) -> String {
+    st.to_uppercase()
+}
+
+/// Convertit la chaîne en entier (`int`), les espaces autour sont ignorés
+pub fn parse_int(st: &str) -> Result<i32, Error> {
+    st.trim().parse().map_err(|_| Error::InvalidInt(st.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures() {
+        let names: Vec<_> = SIGNATURES.iter().map(|sig| sig.name).collect();
+        assert_eq!(vec!["len", "substring", "split", "contains", "to_upper", "parse_int"], names);
+    }
+
+    #[test]
+    fn len_and_substring() {
+        assert_eq!(5, len("héllo"));
+        assert_eq!(Ok("éll".to_string()), substring("héllo", 1, 4));
+        assert_eq!(Ok(String::new()), substring("héllo", 5, 5));
+        assert_eq!(
+            Err(Error::OutOfBounds { begin: 2, end: 6, len: 5 }),
+            substring("héllo", 2, 6)
+        );
+    }
+
+    #[test]
+    fn split_and_contains() {
+        assert_eq!(vec!["a", "b", "", "c"], split("a,b,,c", ","));
+        assert_eq!(vec!["é", "t", "é"], split("été", ""));
+        assert!(contains("bonjour", "jour"));
+        assert!(!contains("bonjour", "soir"));
+    }
+
+    #[test]
+    fn to_upper_and_parse_int() {
+        assert_eq!("ÉTÉ", to_upper("été"));
+        assert_eq!(Ok(-42), parse_int(" -42 "));
+        assert_eq!(Err(Error::InvalidInt("4x".to_string())), parse_int("4x"));
+    }
+}